@@ -0,0 +1,88 @@
+use openai_api_rs::v1::error::APIError;
+use std::path::PathBuf;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Failed to execute command
+    #[error("Could not run command: {0}")]
+    CommandInvocationFailed(#[source] std::io::Error),
+    #[error("Hugo command failed:\n{0}")]
+    HugoCommandFailed(String),
+    #[error("Invalid YAML: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("This site has only {0} language(s) with a content directory, at least two are required to translate. Found: {1}. Check `languages` and `module.mounts` (or `contentDir`) in your Hugo configuration, or run `doctor`")]
+    NoTranslationPossible(usize, String),
+    #[error("Language '{0}' is not configured")]
+    UnknownLanguage(String),
+    #[error("<{}> is written in '{}' according to its front matter, which is not configured", .0.display(), .1)]
+    UnknownFileLanguage(PathBuf, String),
+    #[error("<{}> is not in the content directory of any language", .0.display())]
+    NotInContentDir(PathBuf),
+    #[error("<{}> is not in the content directory of '{}', so it can't be translated again in place", .0.display(), .1)]
+    NotInLanguageContentDir(PathBuf, String),
+    #[error("File has no name")]
+    FileHasNoName,
+    #[error("Could not read <{}>: {}", .0.display(), .1)]
+    CouldNotReadFile(PathBuf, #[source] std::io::Error),
+    #[error("<{}> is not valid UTF-8 (invalid byte at offset {})", .0.display(), .1)]
+    NotUtf8(PathBuf, usize),
+    #[error("Could not list content files: {0}")]
+    Walk(#[from] walkdir::Error),
+    #[error("No front matter found in <{}>", .0.display())]
+    NoFrontMatterFound(PathBuf),
+    #[error("Could not parse front matter of <{}>: {}", .0.display(), .1)]
+    FrontMatterParsingFailed(PathBuf, #[source] serde_yaml::Error),
+    #[error("Could not parse front matter of <{}>: {}", .0.display(), .1)]
+    InvalidFrontMatter(PathBuf, String),
+    #[error("Field `{}` is defined multiple times in the front matter of <{}>", .1, .0.display())]
+    DuplicateFrontMatterKey(PathBuf, String),
+    #[error("No translation key in <{}>", .0.display())]
+    NoTranslationKey(PathBuf),
+    /// Translator kept producing invalid content
+    #[error("Invalid translation of <{}>: {}", .0.display(), .1)]
+    InvalidTranslation(PathBuf, String),
+    #[error("<{}> is too long to be sent with every translation ({} bytes, at most {})", .0.display(), .1, .2)]
+    ContextTooLong(PathBuf, usize, usize),
+    #[error("Path template renders <{0}>, which is outside the content directory")]
+    InvalidPathTemplate(String),
+    #[error("Refusing to write <{}>, which is outside <{}>. Check content directories in your Hugo configuration, or use `--allow-writes-outside-root`", .0.display(), .1.display())]
+    WriteOutsideRoot(PathBuf, PathBuf),
+    #[error("Nothing can be translated with `--prompt-only`, prompts are only rendered")]
+    PromptOnly,
+    #[error("Interrupted")]
+    Interrupted,
+    #[error("Could not handle Ctrl-C: {0}")]
+    CtrlC(#[from] ctrlc::Error),
+    #[error("Hugo build failed, {0}")]
+    BuildFailed(String),
+    #[error("Review failed: {0}")]
+    EditorFailed(String),
+    #[error("Invalid `.translationignore`: {0}")]
+    TranslationIgnore(#[from] ignore::Error),
+    #[error("Language selection failed: {0}")]
+    Prompt(#[from] dialoguer::Error),
+    #[error("Another run is translating this site (process {}), delete <{}> if it's not running anymore or use `--no-lock`", .1, .0.display())]
+    Locked(PathBuf, String),
+    #[error("Plan format version {0} is not supported (expected {1}), plan translations again")]
+    UnsupportedPlanVersion(u32, u32),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Could not load `.env`: {0}")]
+    Env(#[from] dotenvy::Error),
+    #[error("Set `{0}` (in the environment or `.env`) to {1}")]
+    MissingEnvVar(&'static str, &'static str),
+    #[error("Clipboard error: {0}")]
+    Clipboard(String),
+    #[error("OpenAI error: {0}")]
+    OpenAI(#[from] APIError),
+    #[error("Could not list OpenAI models: {0}")]
+    ModelListFailed(String),
+    #[error("Model '{0}' is not available, available models: {1}")]
+    UnknownModel(String, String),
+    #[error("Unexpected OpenAI response: {0}")]
+    UnexpectedResponse(String),
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+
+/// File, relative to the site root, in which failed translations are saved for `--retry-failed`.
+const FAILURES_FILE_NAME: &str = ".rhesus-failures.json";
+
+/// Translation which failed during the previous run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailedTranslation {
+    /// Path of the source file, relative to the site root.
+    pub source: PathBuf,
+    pub from_lang: String,
+    pub to_lang: String,
+    /// Path of the translation which was being translated again, relative to the site root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub existing_translation: Option<PathBuf>,
+}
+
+pub fn load(root: &Path) -> Result<Vec<FailedTranslation>> {
+    let path = root.join(FAILURES_FILE_NAME);
+    if !path.exists() {
+        return Ok(vec![])
+    }
+
+    let json = fs::read_to_string(&path)
+        .map_err(|err| Error::CouldNotReadFile(path.clone(), err))?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// Records `failures` with the ones of previous runs, except `succeeded` translations,
+/// or removes the file if there are none left.
+///
+/// NOTE: Failures of previous runs which were not retried (e.g. interrupted `--retry-failed` runs) are kept.
+pub fn save(root: &Path, succeeded: &[FailedTranslation], failures: &[FailedTranslation]) -> Result<()> {
+    let path = root.join(FAILURES_FILE_NAME);
+    let mut records = load(root)?;
+    // A translation is recorded once, whatever the translation it was replacing.
+    let is_same = |record: &FailedTranslation, other: &FailedTranslation| record.source == other.source && record.to_lang == other.to_lang;
+    records.retain(|record| !succeeded.iter().chain(failures).any(|other| is_same(record, other)));
+    records.extend(failures.iter().cloned());
+
+    if records.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        return Ok(())
+    }
+
+    fs::write(path, serde_json::to_string_pretty(&records)?)?;
+    Ok(())
+}
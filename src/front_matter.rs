@@ -0,0 +1,540 @@
+use serde::Deserialize;
+use serde_yaml::value::{Tag, TaggedValue};
+use serde_yaml::{Mapping, Value};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+
+/// [Front matter format](https://gohugo.io/content-management/front-matter/), detected from its delimiters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrontMatterFormat {
+    /// Delimited by `---` lines.
+    Yaml,
+    /// Delimited by `+++` lines.
+    Toml,
+    /// A JSON object (`{…}`), braces included.
+    Json,
+}
+
+/// YAML tag of TOML dates and times in front matter fields, so they are written back as such.
+const TOML_DATETIME_TAG: &str = "toml-datetime";
+
+/// Content file split into its front matter and body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document<'a> {
+    pub format: FrontMatterFormat,
+    /// Lines between the first two `---` (or `+++`) lines, or lines of the JSON object.
+    pub front_matter: Vec<&'a str>,
+    /// Lines after the front matter.
+    pub body: Vec<&'a str>,
+}
+
+impl<'a> Document<'a> {
+    pub fn split(file_content: &'a str) -> Option<Self> {
+        // Some editors and CMS exports start files with a byte order mark.
+        let file_content = file_content.strip_prefix('\u{feff}').unwrap_or(file_content);
+
+        // Split the file content by lines
+        let lines: Vec<&str> = file_content.split('\n').collect();
+
+        // The front matter must start the file, otherwise `---` lines are thematic breaks in the body.
+        let start = lines.iter().position(|line| !line.trim().is_empty())?;
+        // NOTE: Shortcodes (`{{< … >}}`) can start a body, they are not JSON objects.
+        if lines[start].starts_with('{') && !lines[start].starts_with("{{") {
+            let end = start + json_object_end(&lines[start..])?;
+            return Some(Self {
+                format: FrontMatterFormat::Json,
+                front_matter: lines[start..=end].to_vec(),
+                body: lines[end + 1..].to_vec(),
+            })
+        }
+
+        let (format, delimiter) = match lines[start].trim_end() {
+            "---" => (FrontMatterFormat::Yaml, "---"),
+            "+++" => (FrontMatterFormat::Toml, "+++"),
+            _ => return None,
+        };
+        // NOTE: Trailing whitespace (including `\r` of CRLF line endings) is allowed after delimiters,
+        //   and the closing one can be the last line without a trailing newline.
+        let end = start + 1 + lines[start + 1..].iter().position(|line| line.trim_end() == delimiter)?;
+
+        Some(Self {
+            format,
+            front_matter: lines[start + 1..end].to_vec(),
+            body: lines[end + 1..].to_vec(),
+        })
+    }
+
+    /// Whether the body starts with what looks like a second front matter block
+    /// (e.g. after a bad merge), which Hugo would render as content.
+    ///
+    /// NOTE: A thematic break (`---`) can start a body, so the block must end with another `---` line
+    ///   and only contain fields.
+    pub fn body_starts_with_front_matter(&self) -> bool {
+        let body = self.body.join("\n");
+        let second = Document::split(&body)
+            .filter(|document| self.format == FrontMatterFormat::Yaml && document.format == FrontMatterFormat::Yaml)
+            .map(|document| document.front_matter.join("\n"));
+        let Some(second) = second else {
+            return false
+        };
+        let mut lines = second.lines().filter(|line| !line.trim().is_empty()).peekable();
+        lines.peek().is_some() && lines.all(|line| line.starts_with([' ', '\t', '-', '#']) || top_level_key(line).is_some())
+    }
+}
+
+/// Index of the line which closes the JSON object `lines` start with, if it ends a line.
+fn json_object_end(lines: &[&str]) -> Option<usize> {
+    let mut depth = 0;
+    let (mut in_string, mut escaped) = (false, false);
+    for (index, line) in lines.iter().enumerate() {
+        for (position, c) in line.char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' if in_string => escaped = true,
+                '"' => in_string = !in_string,
+                '{' | '[' if !in_string => depth += 1,
+                '}' | ']' if !in_string => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return line[position + 1..].trim().is_empty().then_some(index)
+                    }
+                },
+                _ => {},
+            }
+        }
+    }
+    None
+}
+
+/// Splits a content file into its front matter (delimiters included) and its body, without changing any byte.
+pub fn split_raw(file_content: &str) -> Option<(&str, &str)> {
+    let document = Document::split(file_content)?;
+    // The body is the end of the file, as lines are split on `\n` only.
+    let body_length = document.body.join("\n").len();
+    Some(file_content.split_at(file_content.len() - body_length))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrontMatter {
+    pub format: FrontMatterFormat,
+    /// Comment lines before the first field (e.g. `# GENERATED BY …`), JSON has no comments.
+    pub comments: Vec<String>,
+    pub fields: Mapping,
+    pub translation_key: Option<TranslationKeys>,
+    pub no_translate: Option<NoTranslate>,
+}
+
+impl FrontMatter {
+    pub fn parse(file_content: &str, path: &Path, translation_key_field: &str) -> Result<Self> {
+        let Some(document) = Document::split(file_content) else {
+            return Err(Error::NoFrontMatterFound(path.to_path_buf()))
+        };
+        Self::from_lines(&document.front_matter, document.format, path, translation_key_field)
+    }
+
+    fn from_lines(lines: &[&str], format: FrontMatterFormat, path: &Path, translation_key_field: &str) -> Result<Self> {
+        // Some YAML parsers keep the last value of duplicated keys, make sure it's not silently ignored.
+        // NOTE: TOML parsers reject duplicated keys already.
+        let mut keys = HashSet::new();
+        if format == FrontMatterFormat::Yaml {
+            if let Some(key) = lines.iter().filter_map(|line| top_level_key(line)).find(|key| !keys.insert(*key)) {
+                return Err(Error::DuplicateFrontMatterKey(path.to_path_buf(), key.to_string()))
+            }
+        }
+
+        let comments = lines.iter()
+            .take_while(|line| format != FrontMatterFormat::Json && line.trim_start().starts_with('#'))
+            .map(|line| line.to_string())
+            .collect();
+
+        // Join the lines between the first two '---' markers
+        let content = lines.join("\n");
+
+        // Parse the content into a generic map, as the translation key field is configurable
+        let parsing_error = |err| Error::FrontMatterParsingFailed(path.to_path_buf(), err);
+        let value = match format {
+            FrontMatterFormat::Yaml => serde_yaml::from_str::<Value>(&content).map_err(parsing_error)?,
+            FrontMatterFormat::Toml => toml::from_str::<toml::Value>(&content)
+                .map(toml_to_yaml)
+                .map_err(|err| Error::InvalidFrontMatter(path.to_path_buf(), err.to_string()))?,
+            FrontMatterFormat::Json => serde_json::from_str::<serde_json::Value>(&content)
+                .map_err(|err| Error::InvalidFrontMatter(path.to_path_buf(), err.to_string()))
+                .and_then(|value| serde_yaml::to_value(value).map_err(parsing_error))?,
+        };
+        let fields = match value {
+            Value::Null => Mapping::new(),
+            value => serde_yaml::from_value::<Mapping>(value).map_err(parsing_error)?,
+        };
+        let translation_key = fields.get(translation_key_field)
+            .map(|value| serde_yaml::from_value::<TranslationKeys>(value.clone()))
+            .transpose()
+            .map_err(parsing_error)?;
+        let no_translate = fields.get("noTranslate")
+            .map(|value| serde_yaml::from_value::<NoTranslate>(value.clone()))
+            .transpose()
+            .map_err(parsing_error)?;
+        let front_matter = FrontMatter { format, comments, fields, translation_key, no_translate };
+        // println!("Parsed frontmatter: {:#?}", front_matter);
+
+        Ok(front_matter)
+    }
+
+    /// Serializes the front matter in its format (delimiters included) with a consistent formatting,
+    /// so files written by the tool always parse back to the same fields.
+    ///
+    /// NOTE: Only leading comments are kept, `serde_yaml` and `toml` drop the other ones.
+    pub fn serialize(&self) -> Result<String> {
+        if self.format == FrontMatterFormat::Json {
+            return Ok(format!("{}\n", serde_json::to_string_pretty(&yaml_to_json(Value::Mapping(self.fields.clone())))?))
+        }
+
+        let delimiter = if self.format == FrontMatterFormat::Toml { "+++\n" } else { "---\n" };
+        let mut text = String::from(delimiter);
+        for comment in self.comments.iter() {
+            text.push_str(comment);
+            text.push('\n');
+        }
+        if !self.fields.is_empty() {
+            match self.format {
+                FrontMatterFormat::Toml => {
+                    let table = self.fields.clone().into_iter()
+                        .filter_map(|(key, value)| Some((yaml_key(key), yaml_to_toml(value)?)))
+                        .collect::<toml::Table>();
+                    text.push_str(&toml::to_string(&table).map_err(|err| Error::InvalidFrontMatter(PathBuf::new(), err.to_string()))?);
+                },
+                _ => text.push_str(&serde_yaml::to_string(&self.fields)?),
+            }
+        }
+        text.push_str(delimiter);
+
+        Ok(text)
+    }
+}
+
+/// Converts TOML values to the YAML values front matter fields are handled as,
+/// tagging dates and times (which YAML has no type for) so they are written back as such.
+fn toml_to_yaml(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(string) => Value::String(string),
+        toml::Value::Integer(integer) => Value::from(integer),
+        toml::Value::Float(float) => Value::from(float),
+        toml::Value::Boolean(boolean) => Value::Bool(boolean),
+        toml::Value::Datetime(datetime) => Value::Tagged(Box::new(TaggedValue {
+            tag: Tag::new(TOML_DATETIME_TAG),
+            value: Value::String(datetime.to_string()),
+        })),
+        toml::Value::Array(array) => Value::Sequence(array.into_iter().map(toml_to_yaml).collect()),
+        toml::Value::Table(table) => Value::Mapping(table.into_iter().map(|(key, value)| (Value::String(key), toml_to_yaml(value))).collect()),
+    }
+}
+
+/// Converts front matter fields back to TOML values, returning `None` for nulls (TOML has none).
+fn yaml_to_toml(value: Value) -> Option<toml::Value> {
+    Some(match value {
+        Value::Null => return None,
+        Value::Bool(boolean) => toml::Value::Boolean(boolean),
+        Value::Number(number) => number.as_i64().map(toml::Value::Integer)
+            .unwrap_or_else(|| toml::Value::Float(number.as_f64().unwrap_or_default())),
+        Value::String(string) => toml::Value::String(string),
+        Value::Sequence(sequence) => toml::Value::Array(sequence.into_iter().filter_map(yaml_to_toml).collect()),
+        Value::Mapping(mapping) => toml::Value::Table(mapping.into_iter()
+            .filter_map(|(key, value)| Some((yaml_key(key), yaml_to_toml(value)?)))
+            .collect()),
+        Value::Tagged(tagged) if tagged.tag == Tag::new(TOML_DATETIME_TAG) => match &tagged.value {
+            Value::String(datetime) => datetime.parse().map(toml::Value::Datetime).unwrap_or_else(|_| toml::Value::String(datetime.clone())),
+            value => yaml_to_toml(value.clone())?,
+        },
+        Value::Tagged(tagged) => yaml_to_toml(tagged.value)?,
+    })
+}
+
+/// Converts front matter fields to JSON values, written as JSON front matter.
+fn yaml_to_json(value: Value) -> serde_json::Value {
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Bool(boolean) => serde_json::Value::Bool(boolean),
+        Value::Number(number) => serde_json::to_value(number).unwrap_or_default(),
+        Value::String(string) => serde_json::Value::String(string),
+        Value::Sequence(sequence) => serde_json::Value::Array(sequence.into_iter().map(yaml_to_json).collect()),
+        Value::Mapping(mapping) => serde_json::Value::Object(mapping.into_iter().map(|(key, value)| (yaml_key(key), yaml_to_json(value))).collect()),
+        Value::Tagged(tagged) => yaml_to_json(tagged.value),
+    }
+}
+
+/// Key of a TOML table or JSON object, which can only be a string.
+fn yaml_key(key: Value) -> String {
+    match key {
+        Value::String(key) => key,
+        key => serde_yaml::to_string(&key).map(|key| key.trim_end().to_string()).unwrap_or_default(),
+    }
+}
+
+/// Rewrites the front matter of a file written by a translator after applying `edit` to its fields.
+/// The body is kept as-is.
+///
+/// Only edited fields are rewritten, so comments and blank lines are preserved elsewhere.
+/// Comments inside edited fields are lost though, and if edited fields can't be located
+/// (e.g. flow style front matter), the whole front matter is reformatted and only leading comments are kept.
+///
+/// The front matter is written in `format` (the format of the source) whatever the format of `file_content`
+/// (e.g. translators which answer YAML for a TOML source), and only YAML front matter is spliced.
+pub fn rewrite(
+    file_content: &str,
+    path: &Path,
+    translation_key_field: &str,
+    format: FrontMatterFormat,
+    edit: impl FnOnce(&mut Mapping),
+) -> Result<String> {
+    let Some(document) = Document::split(file_content) else {
+        return Err(Error::NoFrontMatterFound(path.to_path_buf()))
+    };
+    let mut front_matter = FrontMatter::from_lines(&document.front_matter, document.format, path, translation_key_field)?;
+    let original_fields = front_matter.fields.clone();
+    edit(&mut front_matter.fields);
+
+    let body = document.body.join("\n");
+    if document.format != format || format != FrontMatterFormat::Yaml {
+        let front_matter = FrontMatter { format, ..front_matter };
+        return Ok(format!("{}{}", front_matter.serialize()?, body))
+    }
+    if let Some(lines) = splice(&document.front_matter, &original_fields, &front_matter.fields)? {
+        // Make sure splicing didn't change the meaning of the front matter.
+        let spliced = FrontMatter::from_lines(&lines.iter().map(String::as_str).collect::<Vec<_>>(), format, path, translation_key_field);
+        if spliced.is_ok_and(|spliced| spliced.fields == front_matter.fields) {
+            return Ok(format!("---\n{}---\n{}", lines.iter().map(|line| format!("{}\n", line)).collect::<String>(), body))
+        }
+    }
+
+    Ok(format!("{}{}", front_matter.serialize()?, body))
+}
+
+/// Replaces the lines of top-level fields which differ between `original` and `edited`,
+/// or returns `None` if one of them can't be located.
+fn splice(lines: &[&str], original: &Mapping, edited: &Mapping) -> Result<Option<Vec<String>>> {
+    let mut lines: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+    let changed_keys = original.keys().chain(edited.keys())
+        .filter(|key| original.get(*key) != edited.get(*key))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    for key in changed_keys {
+        let Some(key_name) = key.as_str() else { return Ok(None) };
+        let replacement: Vec<String> = match edited.get(&key) {
+            Some(value) => {
+                let mut field = Mapping::new();
+                field.insert(key.clone(), value.clone());
+                serde_yaml::to_string(&field)?.lines().map(String::from).collect()
+            },
+            None => vec![],
+        };
+
+        let start = lines.iter().position(|line| top_level_key(line) == Some(key_name));
+        match start {
+            Some(start) => {
+                // A field continues on indented lines, and on list items (which can be at the same indentation).
+                let end = start + 1 + lines[start + 1..].iter()
+                    .take_while(|line| line.starts_with([' ', '\t', '-']))
+                    .count();
+                lines.splice(start..end, replacement);
+            },
+            None if original.contains_key(&key) => return Ok(None),
+            None => lines.extend(replacement),
+        }
+    }
+
+    Ok(Some(lines))
+}
+
+/// Name of the top-level field defined on a front matter line, if any.
+fn top_level_key(line: &str) -> Option<&str> {
+    if line.starts_with([' ', '\t', '-', '#']) {
+        return None
+    }
+    let (key, _) = line.split_once(':')?;
+    Some(key.trim().trim_matches(|c| c == '"' || c == '\''))
+}
+
+/// What to do with the [`aliases`](https://gohugo.io/content-management/urls/#aliases)
+/// of the source file in translations.
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum AliasesMode {
+    /// Copy aliases as-is (absolute aliases will conflict between languages).
+    Keep,
+    /// Remove aliases from translations.
+    Strip,
+    /// Prefix absolute aliases with the target language (e.g. `/fr/old-url`).
+    Namespace,
+}
+
+/// Replaces the aliases of a translation by the ones of the source (translators shouldn't change them),
+/// according to `mode`.
+///
+/// Returns absolute aliases which are now emitted for multiple languages.
+pub fn apply_aliases(fields: &mut Mapping, source: &Mapping, mode: AliasesMode, to_lang: &str) -> Vec<String> {
+    let aliases: Vec<String> = source.get("aliases")
+        .and_then(|value| serde_yaml::from_value(value.clone()).ok())
+        .unwrap_or_default();
+
+    // NOTE: Relative aliases are resolved from the page URL, which already differs between languages.
+    let (aliases, duplicates) = match mode {
+        AliasesMode::Keep => {
+            let duplicates = aliases.iter().filter(|alias| alias.starts_with('/')).cloned().collect();
+            (aliases, duplicates)
+        },
+        AliasesMode::Strip => (vec![], vec![]),
+        AliasesMode::Namespace => {
+            let aliases = aliases.into_iter()
+                .map(|alias| if alias.starts_with('/') { format!("/{}{}", to_lang, alias) } else { alias })
+                .collect();
+            (aliases, vec![])
+        },
+    };
+    if aliases.is_empty() {
+        fields.remove("aliases");
+    } else {
+        fields.insert(Value::from("aliases"), Value::from(aliases));
+    }
+
+    duplicates
+}
+
+/// Opt-out of translation (`noTranslate` field), either `true` or a list of languages.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum NoTranslate {
+    All(bool),
+    Languages(Vec<String>),
+}
+
+impl NoTranslate {
+    pub fn excludes(&self, lang: &str) -> bool {
+        match self {
+            Self::All(no_translate) => *no_translate,
+            Self::Languages(languages) => languages.iter().any(|l| l == lang),
+        }
+    }
+}
+
+/// The translation key can be a list (e.g. while migrating keys), in which case
+/// the file belongs to all listed translation groups.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum TranslationKeys {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl From<TranslationKeys> for HashSet<String> {
+    fn from(keys: TranslationKeys) -> Self {
+        match keys {
+            TranslationKeys::One(key) => HashSet::from([key]),
+            TranslationKeys::Many(keys) => keys.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path() -> PathBuf {
+        PathBuf::from("content/en/post.md")
+    }
+
+    /// Rewrites `content` without editing it, then checks it parses back to the same front matter.
+    fn assert_round_trip(content: &str, format: FrontMatterFormat) {
+        let front_matter = FrontMatter::parse(content, &path(), "translationKey").unwrap();
+        assert_eq!(front_matter.format, format);
+
+        let rewritten = rewrite(content, &path(), "translationKey", format, |_| {}).unwrap();
+        assert_eq!(FrontMatter::parse(&rewritten, &path(), "translationKey").unwrap(), front_matter);
+        assert!(rewritten.ends_with("\nBody.\n"), "{}", rewritten);
+    }
+
+    #[test]
+    fn delimiter_variants() {
+        // Content, front matter lines, body lines.
+        let cases: [(&str, &[&str], &[&str]); 6] = [
+            ("---\ntitle: Hello\n---\nBody.\n", &["title: Hello"], &["Body.", ""]),
+            ("---\r\ntitle: Hello\r\n---\r\nBody.\r\n", &["title: Hello\r"], &["Body.\r", ""]),
+            ("--- \ntitle: Hello\n---\t\nBody.\n", &["title: Hello"], &["Body.", ""]),
+            ("---\ntitle: Hello\n---", &["title: Hello"], &[]),
+            ("\n---\ntitle: Hello\n---\nBody.\n", &["title: Hello"], &["Body.", ""]),
+            ("---\ntitle: Hello\n---\nBefore.\n\n---\n\nAfter.\n", &["title: Hello"], &["Before.", "", "---", "", "After.", ""]),
+        ];
+        for (content, front_matter, body) in cases {
+            let document = Document::split(content).unwrap_or_else(|| panic!("No front matter in {:?}", content));
+            assert_eq!(document.front_matter, front_matter, "{:?}", content);
+            assert_eq!(document.body, body, "{:?}", content);
+
+            let (raw_front_matter, raw_body) = split_raw(content).unwrap();
+            assert_eq!(format!("{}{}", raw_front_matter, raw_body), content);
+            assert_eq!(raw_body, body.join("\n"), "{:?}", content);
+            let fields = FrontMatter::parse(content, &path(), "translationKey").unwrap().fields;
+            assert_eq!(fields.get("title"), Some(&Value::from("Hello")), "{:?}", content);
+        }
+
+        // A thematic break is not a front matter when it doesn't start the file.
+        assert_eq!(Document::split("Text.\n\n---\n\nMore text.\n---\n"), None);
+        assert_eq!(Document::split("---\ntitle: Hello\n"), None);
+    }
+
+    #[test]
+    fn translation_key_is_stable_after_writes() {
+        let content = "---\ntitle:   Hello\nparams:\n      tags:\n         - a\n         - b\ntranslationKey:\tpost\n---\n\nBody.\n";
+        let front_matter = FrontMatter::parse(content, &path(), "translationKey").unwrap();
+
+        let written = rewrite(content, &path(), "translationKey", FrontMatterFormat::Yaml, |fields| {
+            fields.insert(Value::from("translator"), Value::from("TEST"));
+        }).unwrap();
+        let rewritten = rewrite(&written, &path(), "translationKey", FrontMatterFormat::Yaml, |_| {}).unwrap();
+
+        let reparsed = FrontMatter::parse(&rewritten, &path(), "translationKey").unwrap();
+        assert_eq!(reparsed.translation_key, front_matter.translation_key);
+        assert_eq!(reparsed.fields.get("params"), front_matter.fields.get("params"));
+        assert_eq!(rewritten, written);
+        // Fields which were not edited are written as they were.
+        assert!(written.contains("title:   Hello\nparams:\n      tags:\n"), "{}", written);
+    }
+
+    #[test]
+    fn yaml_round_trip() {
+        assert_round_trip("---\n# GENERATED\ntitle: Hello\ntranslationKey: post\ntags: [a, b]\n---\n\nBody.\n", FrontMatterFormat::Yaml);
+    }
+
+    #[test]
+    fn toml_round_trip() {
+        let content = "+++\n# GENERATED\ntitle = \"Hello\"\ntranslationKey = \"post\"\ndate = 2024-01-02T03:04:05Z\ndraft = false\nweight = 3\n\n[params]\ntags = [\"a\", \"b\"]\n+++\n\nBody.\n";
+        assert_round_trip(content, FrontMatterFormat::Toml);
+
+        let rewritten = rewrite(content, &path(), "translationKey", FrontMatterFormat::Toml, |_| {}).unwrap();
+        assert!(rewritten.starts_with("+++\n# GENERATED\n"), "{}", rewritten);
+        assert!(rewritten.contains("date = 2024-01-02T03:04:05Z\n"), "{}", rewritten);
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let content = "{\n  \"title\": \"Hello {braces}\",\n  \"translationKey\": \"post\",\n  \"params\": { \"tags\": [\"a\", \"b\"] }\n}\n\nBody.\n";
+        assert_round_trip(content, FrontMatterFormat::Json);
+
+        let document = Document::split(content).unwrap();
+        assert_eq!(document.front_matter.first(), Some(&"{"));
+        assert_eq!(document.body, vec!["", "Body.", ""]);
+    }
+
+    #[test]
+    fn translations_are_written_in_the_source_format() {
+        let translation = "---\ntitle: Bonjour\ntranslationKey: post\n---\n\nBody.\n";
+
+        let rewritten = rewrite(translation, &path(), "translationKey", FrontMatterFormat::Toml, |_| {}).unwrap();
+
+        assert_eq!(rewritten, "+++\ntitle = \"Bonjour\"\ntranslationKey = \"post\"\n+++\n\nBody.\n");
+    }
+
+    #[test]
+    fn shortcodes_are_not_json_front_matter() {
+        assert_eq!(Document::split("{{< notice >}}\nText.\n{{< /notice >}}\n"), None);
+    }
+}
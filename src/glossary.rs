@@ -0,0 +1,77 @@
+use serde_yaml::Mapping;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Front matter fields of Hugo's default taxonomies, used when the site doesn't configure any.
+pub const DEFAULT_TAXONOMY_FIELDS: [&str; 2] = ["tags", "categories"];
+
+/// Known translations of taxonomy terms (e.g. tags), so the same term is always translated the same way.
+///
+/// It is seeded from existing translations and grows as translations are saved.
+#[derive(Debug)]
+pub struct Glossary {
+    taxonomy_fields: Vec<String>,
+    /// `(from_lang, to_lang)` → source term → translated term.
+    terms: Mutex<HashMap<(String, String), HashMap<String, String>>>,
+}
+
+impl Glossary {
+    pub fn new(taxonomy_fields: Vec<String>) -> Self {
+        Self {
+            taxonomy_fields,
+            terms: Mutex::default(),
+        }
+    }
+
+    fn taxonomy_terms(&self, fields: &Mapping) -> Vec<(String, Vec<String>)> {
+        self.taxonomy_fields.iter()
+            .filter_map(|field| {
+                let terms = serde_yaml::from_value::<Vec<String>>(fields.get(field.as_str())?.clone()).ok()?;
+                Some((field.clone(), terms))
+            })
+            .collect()
+    }
+
+    /// Records term translations from the front matter of two versions of the same page.
+    ///
+    /// NOTE: Terms are matched by position, so lists with different lengths are ignored.
+    pub fn record(&self, from_lang: &str, to_lang: &str, source: &Mapping, translation: &Mapping) {
+        let translated_terms: HashMap<_, _> = self.taxonomy_terms(translation).into_iter().collect();
+        let mut terms = self.terms.lock().unwrap();
+        for (field, source_terms) in self.taxonomy_terms(source) {
+            let Some(translated_terms) = translated_terms.get(&field) else { continue };
+            if translated_terms.len() != source_terms.len() {
+                continue
+            }
+            for (source_term, translated_term) in source_terms.into_iter().zip(translated_terms.iter()) {
+                // Keep the first known translation, so previous choices are not overridden.
+                terms.entry((from_lang.to_string(), to_lang.to_string()))
+                    .or_default()
+                    .entry(source_term.clone())
+                    .or_insert_with(|| translated_term.clone());
+                terms.entry((to_lang.to_string(), from_lang.to_string()))
+                    .or_default()
+                    .entry(translated_term.clone())
+                    .or_insert(source_term);
+            }
+        }
+    }
+
+    /// Prompt instructions listing known translations of the source's taxonomy terms, if any.
+    pub fn instructions(&self, from_lang: &str, to_lang: &str, source: &Mapping) -> Option<String> {
+        let terms = self.terms.lock().unwrap();
+        let known_terms = terms.get(&(from_lang.to_string(), to_lang.to_string()))?;
+
+        let mut mappings: Vec<String> = self.taxonomy_terms(source).into_iter()
+            .flat_map(|(_, terms)| terms)
+            .filter_map(|term| known_terms.get(&term).map(|translation| format!("\"{}\" → \"{}\"", term, translation)))
+            .collect();
+        if mappings.is_empty() {
+            return None
+        }
+        mappings.sort();
+        mappings.dedup();
+
+        Some(format!("Translate taxonomy terms consistently with other pages: {}.", mappings.join(", ")))
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+
+/// Default file, relative to the site root, in which source hashes are saved for `--since-hash-file`.
+pub const HASHES_FILE_NAME: &str = ".rhesus-hashes.json";
+
+/// Hash of a source file, which changes when its content changes.
+///
+/// NOTE: Uses 64-bit FNV-1a, which is stable across Rust versions and platforms
+///   (unlike `std::hash::DefaultHasher`), as hashes are saved between runs.
+pub fn source_hash(content: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in content.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Hashes of the sources translations were made from, by translation key then language,
+/// so outdated translations can be found without storing hashes in their front matter.
+#[derive(Debug, Default)]
+pub struct SourceHashes {
+    path: PathBuf,
+    hashes: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl SourceHashes {
+    /// Loads hashes from `path` (relative to the site root), if the file exists.
+    pub fn load(root: &Path, path: &Path) -> Result<Self> {
+        let path = root.join(path);
+        if !path.exists() {
+            return Ok(Self { path, hashes: BTreeMap::new() })
+        }
+
+        let json = fs::read_to_string(&path)
+            .map_err(|err| Error::CouldNotReadFile(path.clone(), err))?;
+        Ok(Self { hashes: serde_json::from_str(&json)?, path })
+    }
+
+    /// Hash of the source the `lang` translation of `translation_key` was made from, if known.
+    pub fn get(&self, translation_key: &str, lang: &str) -> Option<&String> {
+        self.hashes.get(translation_key)?.get(lang)
+    }
+
+    pub fn set(&mut self, translation_key: &str, lang: &str, hash: String) {
+        self.hashes.entry(translation_key.to_string()).or_default().insert(lang.to_string(), hash);
+    }
+
+    pub fn save(&self) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(&self.hashes)?)?;
+        Ok(())
+    }
+}
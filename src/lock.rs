@@ -0,0 +1,58 @@
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+
+/// File, relative to the site root, which exists while a run translates the site.
+const LOCK_FILE_NAME: &str = ".rhesus.lock";
+
+/// Advisory lock preventing overlapping runs from writing the same translations, released when dropped.
+#[derive(Debug)]
+pub struct RunLock {
+    path: PathBuf,
+}
+
+impl RunLock {
+    pub fn acquire(root: &Path) -> Result<Self> {
+        let path = root.join(LOCK_FILE_NAME);
+        // NOTE: `create_new` fails if the file exists, atomically, so two runs can't both acquire the lock.
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                write!(file, "{}", std::process::id())?;
+                Ok(Self { path })
+            },
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => {
+                let pid = fs::read_to_string(&path).unwrap_or_default().trim().to_string();
+                // NOTE: Runs which were killed (e.g. `SIGKILL` or a crash) can't remove their lock.
+                if is_dead(&pid) {
+                    eprintln!("Warning: Taking over the lock of process {}, which is not running anymore", pid);
+                    fs::remove_file(&path)?;
+                    return Self::acquire(root)
+                }
+                Err(Error::Locked(path, pid))
+            },
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for RunLock {
+    fn drop(&mut self) {
+        if let Err(err) = fs::remove_file(&self.path) {
+            eprintln!("Warning: Could not remove lock file <{}>: {}", self.path.display(), err);
+        }
+    }
+}
+
+/// Whether the process `pid` is known not to be running anymore.
+///
+/// NOTE: Processes are listed in `/proc` on Linux only, elsewhere locks are never considered stale.
+fn is_dead(pid: &str) -> bool {
+    let proc = Path::new("/proc");
+    proc.is_dir() && pid.parse::<u32>().is_ok_and(|pid| !proc.join(pid.to_string()).exists())
+}
@@ -1,21 +1,106 @@
+/// Prints a status message, unless `--quiet` is set.
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if !$crate::QUIET.load(std::sync::atomic::Ordering::Relaxed) {
+            println!($($arg)*);
+        }
+    };
+}
+
+mod error;
+mod failures;
+mod front_matter;
+mod glossary;
+mod hashes;
+mod lock;
+mod merge;
+mod plan;
+mod report;
+mod stats;
 mod translator;
 
 use clap::Parser;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use indexmap::IndexMap;
-use serde::Deserialize;
-use std::collections::{HashMap, HashSet};
-use std::fs;
-use std::path::PathBuf;
-use std::process::Command;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Mapping;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::{env, fs, io};
+use std::io::{IsTerminal, Write};
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, ExitCode};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use walkdir::WalkDir;
 
+use crate::error::{Error, Result};
+use crate::failures::FailedTranslation;
+use crate::front_matter::{AliasesMode, Document, FrontMatter, NoTranslate};
+use crate::glossary::Glossary;
+use crate::hashes::SourceHashes;
+use crate::lock::RunLock;
+use crate::merge::SourceSnapshots;
+use crate::report::{Outcome, Report};
+use crate::stats::Stats;
+use crate::translator::{Content, Translator};
+
 /// TODO
+// NOTE: Arguments don't conflict with subcommands, otherwise global arguments couldn't be passed
+//   before a subcommand (e.g. `rhesus-macaque --root site list`).
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
-struct Args {
-    /// Path to the root of the website.
-    #[arg(long)]
+struct Cli {
+    #[command(flatten)]
+    global: GlobalArgs,
+    #[command(subcommand)]
+    command: Option<Subcommand>,
+    // Arguments of `translate`, which runs when no subcommand is given.
+    #[command(flatten)]
+    translate: Args,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Subcommand {
+    /// Translate pages missing in some languages (default).
+    Translate(Args),
+    /// Exit with an error if some pages are missing in some languages.
+    Check(Args),
+    /// List pages missing in some languages.
+    List(Args),
+    /// Print the effective configuration (secrets redacted).
+    Config,
+    /// Diagnose setup problems, without translating anything.
+    Doctor {
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+}
+
+/// Arguments shared by all subcommands.
+#[derive(clap::Args, Debug, Clone, Default, Serialize)]
+struct GlobalArgs {
+    /// Path to the root of the website (defaults to the current directory).
+    #[arg(long, global = true, default_value = ".")]
     root: PathBuf,
+    /// Hugo executable.
+    #[arg(long, global = true, default_value = "hugo")]
+    hugo_bin: PathBuf,
+    /// Language to use instead of Hugo's `defaultContentLanguage` (e.g. to translate from the language
+    /// content is written in, when it's not the one published by default).
+    #[arg(long, global = true, value_name = "LANG")]
+    default_content_language: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+struct Args {
+    // NOTE: Set from `Cli::global`, as global arguments are parsed by the top-level command.
+    #[arg(skip)]
+    global: GlobalArgs,
     /// Do not translate.
     #[arg(long, default_value_t = false)]
     dry_run: bool,
@@ -25,6 +110,216 @@ struct Args {
     /// Translate draft pages.
     #[arg(long, default_value_t = false)]
     drafts: bool,
+    /// Translate draft pages, forcing `draft: true` in their translations.
+    #[arg(long, default_value_t = false, conflicts_with = "drafts")]
+    include_drafts_as_drafts: bool,
+    /// Translate pages with a `publishDate` in the future.
+    #[arg(long, default_value_t = false)]
+    future: bool,
+    /// Translate pages with an `expiryDate` in the past.
+    #[arg(long, default_value_t = false)]
+    expired: bool,
+    /// Write translations in this directory instead of the languages' content directories
+    /// (e.g. for review before copying them into place).
+    #[arg(long)]
+    output_dir: Option<PathBuf>,
+    /// List files which were skipped because they can't be translated.
+    #[arg(long, default_value_t = false)]
+    list_skipped: bool,
+    /// Also translate HTML content files (`.html`).
+    #[arg(long, default_value_t = false)]
+    html: bool,
+    /// Number of translations to run in parallel (capped by what the translator supports).
+    #[arg(long, short = 'j', default_value_t = 1)]
+    jobs: usize,
+    /// Number of times an invalid translation (e.g. missing front matter or `translationKey`) is retried.
+    #[arg(long, default_value_t = 2)]
+    max_retries: usize,
+    /// Language to translate from, for translation groups which have a file in this language.
+    /// Other groups are translated from their file in the default content language,
+    /// or in the language with the lowest weight.
+    #[arg(long, visible_alias = "from")]
+    source_lang: Option<String>,
+    /// Normalize translated paths to URL-safe slugs (lowercase, dashes, no accents).
+    #[arg(long, default_value_t = false)]
+    slugify_paths: bool,
+    /// File containing the prompt template used for section pages (`_index` files),
+    /// see `translator::Prompts` for available placeholders.
+    #[arg(long)]
+    section_prompt_template: Option<PathBuf>,
+    /// File containing the prompt template used for regular pages, see `translator::Prompts` for available placeholders.
+    #[arg(long)]
+    prompt_template: Option<PathBuf>,
+    /// Directory containing prompt templates for specific language pairs, named `{from}-{to}.txt`
+    /// (and `{from}-{to}.section.txt` for section pages). They take precedence over other templates.
+    #[arg(long, value_name = "DIR")]
+    pair_prompt_templates: Option<PathBuf>,
+    /// Only retry translations which failed during previous runs (and haven't been translated since).
+    #[arg(long, default_value_t = false)]
+    retry_failed: bool,
+    /// Front matter field containing the translation key.
+    #[arg(long, default_value = "translationKey")]
+    translation_key_field: String,
+    /// Only print warnings and errors.
+    #[arg(long, short = 'q', default_value_t = false)]
+    quiet: bool,
+    /// Translate a single document read from stdin, and write it to stdout.
+    #[arg(long, default_value_t = false, requires_all = ["source_lang", "target_lang"])]
+    stdin: bool,
+    /// What to do with the `aliases` of source files in translations.
+    #[arg(long, value_enum, default_value_t = AliasesMode::Keep)]
+    aliases: AliasesMode,
+    /// Open each translation in `$VISUAL` or `$EDITOR` before saving it.
+    #[arg(long, default_value_t = false, conflicts_with_all = ["jobs", "quiet", "stdin", "dry_run"])]
+    review: bool,
+    /// Build the site after translating, and fail if the build fails (slow).
+    #[arg(long, default_value_t = false)]
+    verify_build: bool,
+    /// Format of the end-of-run summary (`json` implies `--quiet`).
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    format: OutputFormat,
+    /// Do not translate section pages (`_index` files).
+    #[arg(long, default_value_t = false, conflicts_with = "only_sections")]
+    skip_sections: bool,
+    /// Only translate section pages (`_index` files).
+    #[arg(long, default_value_t = false)]
+    only_sections: bool,
+    /// Template of translated file paths (relative to the target content directory), used instead of
+    /// translating paths. Placeholders: `{lang}`, `{from_lang}`, `{dir}`, `{slug}` (file stem) and `{ext}`.
+    #[arg(long)]
+    path_template: Option<String>,
+    /// Milliseconds to wait after each translator call (e.g. to be gentle with a self-hosted model).
+    #[arg(long, value_name = "MS")]
+    delay: Option<u64>,
+    /// Translate all languages of a page in the same conversation, for a consistent terminology.
+    /// Prompts get longer (and more expensive) with each language, as the conversation is sent every time.
+    #[arg(long, default_value_t = false, requires = "auto")]
+    threads_per_key: bool,
+    /// Delete generated translations whose source (in the default content language) doesn't exist anymore.
+    #[arg(long, default_value_t = false)]
+    sync: bool,
+    /// Only translate this number of random jobs (e.g. to review a translator's output before a full run).
+    #[arg(long, value_name = "N")]
+    sample: Option<usize>,
+    /// Seed used to select `--sample` jobs (random by default).
+    #[arg(long, requires = "sample")]
+    seed: Option<u64>,
+    /// Do not check the OpenAI model is available before translating.
+    #[arg(long, default_value_t = false)]
+    skip_model_check: bool,
+    /// Only translate files in this section (path relative to content directories, e.g. `blog`), can be repeated.
+    /// Defaults to `params.translationScope` in the Hugo configuration, or all sections.
+    #[arg(long, value_name = "PATH")]
+    scope: Vec<PathBuf>,
+    /// Also translate again generated translations which were modified before their source.
+    /// Less reliable than comparing contents, as modification times can be misleading (e.g. after `git clone`).
+    #[arg(long, default_value_t = false)]
+    stale_by_mtime: bool,
+    /// Template of the `translator` front matter value written in translations (e.g. `"{tool} {version} / {generator}"`).
+    /// Placeholders: `{tool}`, `{version}`, `{generator}` (e.g. the model name) and `{date}`. Defaults to the generator.
+    #[arg(long)]
+    translator_template: Option<String>,
+    /// Choose the languages to translate into in a terminal menu (ignored when not run in a terminal).
+    #[arg(long, short = 'i', default_value_t = false, conflicts_with_all = ["stdin", "retry_failed"])]
+    interactive: bool,
+    /// Print prompts (path and content) of all translation jobs instead of translating,
+    /// to translate in another tool. Nothing is written in content directories.
+    #[arg(long, default_value_t = false, conflicts_with_all = ["dry_run", "auto", "review", "stdin", "sync"])]
+    prompt_only: bool,
+    /// Write `--prompt-only` prompts in this directory (one file per job) instead of stdout.
+    #[arg(long, value_name = "DIR", requires = "prompt_only")]
+    prompt_dir: Option<PathBuf>,
+    /// Front matter fields containing lists of strings (e.g. `keywords`) to translate item by item,
+    /// instead of relying on the content translation to keep their structure.
+    #[arg(long, value_name = "FIELD", value_delimiter = ',')]
+    list_fields: Vec<String>,
+    /// Stop starting new translations after this duration (e.g. `90s`, `20m`, `1h`, seconds by default),
+    /// translations in progress are finished. Exits with status 4 if some translations were not started.
+    #[arg(long, value_parser = parse_duration)]
+    timeout: Option<Duration>,
+    /// Translate all pages into a language which was just added to the configuration
+    /// (its content directory is created if needed). Other languages are not translated.
+    #[arg(long, value_name = "LANG", conflicts_with_all = ["stdin", "retry_failed"])]
+    bootstrap: Option<String>,
+    /// Translate at most this number of pages (into all languages), next runs continue with the following ones.
+    #[arg(long, value_name = "N")]
+    max_files: Option<usize>,
+    /// Write a report of the run (translation groups, languages, errors) in this file,
+    /// as HTML if it ends with `.html`, as Markdown otherwise.
+    #[arg(long, value_name = "FILE")]
+    report: Option<PathBuf>,
+    /// Do not prevent other runs from translating the same site at the same time (see `.rhesus.lock`).
+    #[arg(long, default_value_t = false)]
+    no_lock: bool,
+    /// Only translate the paths of missing translations (e.g. for human translators), and print them.
+    #[arg(long, default_value_t = false, conflicts_with_all = ["stdin", "prompt_only", "review", "stale_by_mtime"])]
+    paths_only: bool,
+    /// With `--paths-only`, copy untranslated sources to the translated paths as a starting point.
+    #[arg(long, default_value_t = false, requires = "paths_only")]
+    copy_source: bool,
+    /// Front matter field containing the language of a page (e.g. `lang`), used instead of
+    /// the content directory it's in when set. Translations get this field too.
+    #[arg(long, value_name = "FIELD")]
+    language_field: Option<String>,
+    /// Save planned translation jobs in this file instead of translating them, to review them
+    /// before running them with `--plan-in` (see `plan::Plan` for the format).
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["stdin", "prompt_only", "plan_in"])]
+    plan_out: Option<PathBuf>,
+    /// Run the translation jobs saved with `--plan-out`, instead of planning them.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["stdin", "retry_failed", "sync"])]
+    plan_in: Option<PathBuf>,
+    /// Only translate `--front-matter-fields`, keeping the body as-is.
+    #[arg(long, visible_alias = "frontmatter-only", default_value_t = false, conflicts_with_all = ["body_only", "stdin"])]
+    front_matter_only: bool,
+    /// Front matter fields translated with `--front-matter-only` (string fields only).
+    #[arg(long, value_name = "FIELD", value_delimiter = ',', default_value = "title,linkTitle,description,summary")]
+    front_matter_fields: Vec<String>,
+    /// Only translate the body, keeping the front matter as-is (apart from the `translator` field).
+    #[arg(long, default_value_t = false, conflicts_with = "stdin")]
+    body_only: bool,
+    /// File containing background instructions (e.g. a style guide or terminology) sent with every translation.
+    #[arg(long, value_name = "FILE")]
+    context_file: Option<PathBuf>,
+    /// Models to use, in order, when the OpenAI model is unavailable (e.g. `gpt-4o-mini,gpt-3.5-turbo`).
+    #[arg(long, value_name = "MODELS", value_delimiter = ',', requires = "auto")]
+    model_fallback: Vec<String>,
+    /// Maximum length (in characters) of a translated front matter field, e.g. `description=155` for SEO.
+    /// Translators are asked to respect it, and a warning is printed if they don't. Can be repeated.
+    #[arg(long, value_name = "FIELD=LENGTH", value_parser = parse_length_limit)]
+    max_length: Vec<(String, usize)>,
+    /// Also translate again generated translations whose source changed since they were translated,
+    /// according to source hashes saved in this file (relative to the site root) instead of front matter.
+    #[arg(long, value_name = "FILE", num_args = 0..=1, default_missing_value = hashes::HASHES_FILE_NAME)]
+    since_hash_file: Option<PathBuf>,
+    /// Allow writing translations outside the site root (or `--output-dir`), e.g. for content directories
+    /// mounted from elsewhere.
+    #[arg(long, default_value_t = false)]
+    allow_writes_outside_root: bool,
+    /// With `--since-hash-file`, only translate the paragraphs which changed in the source of stale translations,
+    /// keeping the other ones as they are (e.g. manual tweaks). Sources are saved next to the hashes file for this,
+    /// pages are translated entirely when changes can't be located (e.g. the front matter changed).
+    #[arg(long, default_value_t = false, requires = "since_hash_file", conflicts_with_all = ["front_matter_only", "body_only", "paths_only"])]
+    merge: bool,
+    /// Language to translate into (with `--stdin`).
+    #[arg(long, visible_alias = "to", requires = "stdin")]
+    target_lang: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+enum OutputFormat {
+    Human,
+    Json,
+}
+
+/// End-of-run summary, for `--format json`.
+#[derive(Debug, Clone, Serialize)]
+struct Summary<'a> {
+    translated: usize,
+    failed: Vec<FailedTranslation>,
+    /// Jobs which were not started because of `--timeout` or Ctrl-C.
+    not_started: usize,
+    generator: &'a str,
+    stats: Option<Stats>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -34,17 +329,42 @@ struct HugoConfigDTO {
     #[serde(rename(deserialize = "contentdir"))]
     content_dir: Option<String>,
     languages: HashMap<String, HugoLanguageConfigDTO>,
+    #[serde(default)]
     module: HugoModuleDTO,
+    /// Singular name → plural name (used in front matter).
+    #[serde(default)]
+    taxonomies: HashMap<String, String>,
+    #[serde(default)]
+    params: HugoParamsDTO,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+struct HugoParamsDTO {
+    /// `params.translationScope`, see [`HugoConfig::translation_scope`].
+    #[serde(rename(deserialize = "translationscope"), default)]
+    translation_scope: Vec<PathBuf>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 struct HugoLanguageConfigDTO {
     #[serde(rename(deserialize = "languagename"))]
     language_name: String,
+    #[serde(default)]
+    weight: i64,
+    /// Legacy per-language `contentDir`, superseded by module mounts.
+    #[serde(rename(deserialize = "contentdir"))]
+    content_dir: Option<String>,
+    /// Language tag (e.g. `en-US`), used in HTML `lang` attributes.
+    #[serde(rename(deserialize = "languagecode"), default)]
+    language_code: Option<String>,
+    /// `ltr` or `rtl`.
+    #[serde(rename(deserialize = "languagedirection"), default)]
+    language_direction: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
 struct HugoModuleDTO {
+    #[serde(default)]
     mounts: Vec<HugoMountDTO>,
 }
 
@@ -54,52 +374,108 @@ struct HugoMountDTO {
     source: String,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 struct HugoConfig {
     language_configs: IndexMap<String, HugoLanguageConfig>,
+    default_content_language: String,
+    /// Front matter fields containing taxonomy terms.
+    taxonomy_fields: Vec<String>,
+    /// Paths (relative to content directories) of sections which are translated, if not all of them.
+    translation_scope: Vec<PathBuf>,
 }
 
 impl HugoConfig {
+    /// Human-readable name of a language (`languageName`), if it's configured.
+    fn language_name(&self, lang: &str) -> Option<&str> {
+        self.language_configs.get(lang)
+            .map(|config| config.language_name.as_str())
+            .filter(|name| !name.trim().is_empty())
+    }
+
+    /// Language of the most specific content directory containing `path`,
+    /// the one with the lowest weight if languages share a content directory.
+    fn content_language(&self, path: &Path) -> Option<&String> {
+        self.language_configs.iter()
+            .filter(|(_, config)| path.starts_with(&config.content_dir))
+            // NOTE: `max_by_key` returns the last maximum, iterate in reverse so the first language wins ties.
+            .rev()
+            .max_by_key(|(_, config)| config.content_dir.components().count())
+            .map(|(lang, _)| lang)
+    }
+
     fn new(config: HugoConfigDTO, root: PathBuf) -> HugoConfig {
-        // `content_dirs.keys()` is ordered depending on language weights, no need to do the sorting manually.
         let mut content_dirs: IndexMap<String, PathBuf> = IndexMap::with_capacity(config.languages.len());
         for mount in config.module.mounts {
             if let Some(lang) = mount.lang {
-                content_dirs.insert(lang, root.join(mount.source));
+                content_dirs.insert(lang, resolve_path(&root, mount.source));
+            }
+        }
+        // Sites without per-language mounts can still configure content directories the legacy way.
+        for (lang, language_config) in config.languages.iter() {
+            if let Some(content_dir) = &language_config.content_dir {
+                content_dirs.entry(lang.clone()).or_insert_with(|| resolve_path(&root, content_dir));
             }
         }
+        if config.languages.contains_key(&config.default_content_language) {
+            let content_dir = config.content_dir.as_deref().unwrap_or("content");
+            content_dirs.entry(config.default_content_language.clone()).or_insert_with(|| resolve_path(&root, content_dir));
+        }
 
         let mut language_configs: IndexMap<String, HugoLanguageConfig> = IndexMap::new();
 
-        for language_identifier in content_dirs.keys() {
-            let language_config = config.languages.get(language_identifier)
-                .expect("")
-                .to_owned();
-
-            let content_dir = content_dirs.get(language_identifier)
-                .expect(&format!("Language '{}' has no 'contentDir'", language_identifier))
-                .to_owned();
+        for (language_identifier, content_dir) in content_dirs {
+            let Some(language_config) = config.languages.get(&language_identifier).cloned() else {
+                eprintln!("Warning: <{}> is mounted for language '{}', which is not configured.", content_dir.display(), language_identifier);
+                continue
+            };
 
-            language_configs.insert(language_identifier.to_owned(), HugoLanguageConfig {
+            language_configs.insert(language_identifier, HugoLanguageConfig {
                 content_dir,
                 language_name: language_config.language_name,
+                weight: language_config.weight,
+                language_code: language_config.language_code,
+                language_direction: language_config.language_direction,
             });
         }
 
-        HugoConfig { language_configs }
+        // Mounts order doesn't necessarily follow language weights, sort languages like Hugo does
+        // (ascending weight, unset weights last) with the default content language first on ties.
+        let default_language = &config.default_content_language;
+        language_configs.sort_by(|lang1, config1, lang2, config2| {
+            let weight_order = |weight: i64| (weight == 0, weight);
+            weight_order(config1.weight).cmp(&weight_order(config2.weight))
+                .then_with(|| (lang2 == default_language).cmp(&(lang1 == default_language)))
+                .then_with(|| lang1.cmp(lang2))
+        });
+
+        let mut taxonomy_fields: Vec<String> = config.taxonomies.into_values().collect();
+        if taxonomy_fields.is_empty() {
+            taxonomy_fields = glossary::DEFAULT_TAXONOMY_FIELDS.map(String::from).to_vec();
+        }
+        taxonomy_fields.sort();
+
+        HugoConfig {
+            language_configs,
+            default_content_language: config.default_content_language,
+            taxonomy_fields,
+            translation_scope: config.params.translation_scope,
+        }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 struct HugoLanguageConfig {
     content_dir: PathBuf,
     language_name: String,
+    weight: i64,
+    language_code: Option<String>,
+    language_direction: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
-struct FrontMatter {
-    #[serde(rename(deserialize = "translationKey"))]
-    translation_key: String,
+impl HugoLanguageConfig {
+    fn is_right_to_left(&self) -> bool {
+        self.language_direction.as_deref().is_some_and(|direction| direction.eq_ignore_ascii_case("rtl"))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -107,64 +483,53 @@ struct FileMetadata {
     path: PathBuf,
     language_identifier: String,
     base_name: String,
-    translation_key: String,
+    /// Translation groups this file belongs to.
+    ///
+    /// When a file belongs to multiple groups, a language is considered already translated
+    /// if *any* of the groups contains a file in this language.
+    translation_keys: HashSet<String>,
+    front_matter: Mapping,
+    /// Whether Hugo considers this page a draft (only known when drafts are listed).
+    is_draft: bool,
+    no_translate: Option<NoTranslate>,
+}
+
+/// Reads a content file, reporting files which are not valid UTF-8 (e.g. legacy Latin-1 content).
+fn read_content_file(path: &PathBuf) -> Result<String> {
+    let bytes = fs::read(path).map_err(|err| Error::CouldNotReadFile(path.clone(), err))?;
+    String::from_utf8(bytes).map_err(|err| Error::NotUtf8(path.clone(), err.utf8_error().valid_up_to()))
 }
 
 impl FileMetadata {
-    fn try_from(path: PathBuf, language_identifier: String) -> Result<Self, Error> {
+    fn try_from(path: PathBuf, language_identifier: String, translation_key_field: &str) -> Result<Self> {
         let base_name = path.file_stem().ok_or(Error::FileHasNoName)?.to_string_lossy().to_string();
 
-        let front_matter = {
-            let file_content = fs::read_to_string(&path)
-                .map_err(|err| Error::CouldNotReadFile(path.clone(), err))?;
-
-            // Split the file content by lines
-            let lines: Vec<&str> = file_content.split('\n').collect();
-
-            // Find the start and end indices of the first two '---' lines
-            let mut start_index: Option<usize> = None;
-            let mut end_index: Option<usize> = None;
-
-            for (idx, line) in lines.iter().enumerate() {
-                if line.trim() == "---" {
-                    if start_index.is_none() {
-                        start_index = Some(idx);
-                    } else if end_index.is_none() {
-                        end_index = Some(idx);
-                        break; // Stop when both '---' lines are found
-                    }
-                }
-            }
-
-            let (Some(start), Some(end)) = (start_index, end_index) else {
-                return Err(Error::NoFrontMatterFound(path.clone()))
-            };
-
-            // Join the lines between the first two '---' markers
-            let yaml_lines: Vec<&str> = lines[start + 1..end].to_vec();
-            let yaml_content = yaml_lines.join("\n");
-
-            // Parse YAML content into FrontMatter struct
-            let front_matter = serde_yaml::from_str::<FrontMatter>(&yaml_content)
-                .map_err(Error::FrontMatterParsingFailed)?;
-            // println!("Parsed frontmatter: {:#?}", front_matter);
+        let file_content = read_content_file(&path)?;
+        let front_matter = FrontMatter::parse(&file_content, &path, translation_key_field)?;
+        if Document::split(&file_content).is_some_and(|document| document.body_starts_with_front_matter()) {
+            eprintln!("Warning: <{}> seems to have a second front matter block, only the first one is used.", path.display());
+        }
 
-            Ok(front_matter)
-        }?;
+        let Some(translation_key) = front_matter.translation_key else {
+            return Err(Error::NoTranslationKey(path))
+        };
 
         Ok(Self {
             path,
             language_identifier,
             base_name,
-            translation_key: front_matter.translation_key,
+            translation_keys: translation_key.into(),
+            front_matter: front_matter.fields,
+            is_draft: false,
+            no_translate: front_matter.no_translate,
         })
     }
 }
 
-fn hugo(cmd_args: &Args, hugo_args: Vec<&str>) -> Result<String, Box<Error>> {
-    let output = Command::new("hugo")
-        .args(vec![
-            vec!["-s", &cmd_args.root.display().to_string()],
+fn hugo(global_args: &GlobalArgs, hugo_args: Vec<&str>) -> Result<String> {
+    let output = Command::new(&global_args.hugo_bin)
+        .args([
+            vec!["-s", &global_args.root.display().to_string()],
             hugo_args,
         ].concat())
         .output()
@@ -173,7 +538,7 @@ fn hugo(cmd_args: &Args, hugo_args: Vec<&str>) -> Result<String, Box<Error>> {
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         eprintln!("Command failed with error:\n{}", stderr);
-        return Err(Box::new(Error::HugoCommandFailed(stderr.to_string())))
+        return Err(Error::HugoCommandFailed(stderr.to_string()))
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -182,169 +547,2116 @@ fn hugo(cmd_args: &Args, hugo_args: Vec<&str>) -> Result<String, Box<Error>> {
     Ok(stdout.to_string())
 }
 
-fn draft_files(cmd_args: &Args) -> Result<Vec<PathBuf>, Box<Error>> {
-    // List draft pages using `hugo list drafts` so [front matter cascade](https://gohugo.io/content-management/front-matter/#front-matter-cascade)
-    // is correctly handled.
+/// Resolves a path of the Hugo configuration (absolute, or relative to the site root),
+/// canonicalizing it if it exists (e.g. resolving symbolic links) so paths can be compared reliably.
+fn resolve_path(root: &Path, path: impl AsRef<Path>) -> PathBuf {
+    // NOTE: Joining an absolute path replaces `root`.
+    let path = root.join(path);
+    fs::canonicalize(&path).unwrap_or(path)
+}
+
+/// Pages Hugo doesn't publish, `kind` being `"drafts"`, `"future"` or `"expired"`.
+fn unpublished_files(cmd_args: &Args, kind: &str) -> Result<Vec<PathBuf>> {
+    // List pages using `hugo list {drafts,future,expired}` so [front matter cascade](https://gohugo.io/content-management/front-matter/#front-matter-cascade)
+    // and the various date formats are correctly handled.
     // Output is in CSV format.
-    let stdout = hugo(cmd_args, vec!["list", "drafts"])?;
-    let site_root = &cmd_args.root;
-    let draft_files = stdout.lines()
+    let stdout = hugo(&cmd_args.global, vec!["list", kind])?;
+    let site_root = &cmd_args.global.root;
+    let files = stdout.lines()
         // Skip CSV header row (`path,slug,title,date,expiryDate,publishDate,draft,permalink`)
         .skip(1)
         // Get first element (`path`) for each CSV line
         // NOTE: File paths must not contain commas (`,`)
         // NOTE: Unwrapping here is safe as the CSV line will always contains at least one comma (`,`)
         .map(|l| l.split_once(",").unwrap().0)
-        // Map string path relative to site root to `PathBuf` relative to current working directory
-        .map(|p| site_root.join(p))
+        // Map string path relative to site root to a canonical `PathBuf`, like content directories
+        .map(|p| resolve_path(site_root, p))
         .collect();
 
-    Ok(draft_files)
+    Ok(files)
+}
+
+/// Exit code used when some translations failed. Other errors (e.g. invalid configuration) exit with `1`.
+const TRANSLATIONS_FAILED_EXIT_CODE: u8 = 2;
+/// Exit code of `check` when some pages are missing in some languages.
+const TRANSLATIONS_MISSING_EXIT_CODE: u8 = 3;
+/// Exit code used when `--timeout` was reached before all translations were started.
+const TIMED_OUT_EXIT_CODE: u8 = 4;
+/// Exit code used when the run was interrupted with Ctrl-C (`128 + SIGINT`, like shells).
+const INTERRUPTED_EXIT_CODE: u8 = 130;
+
+/// Whether status messages are hidden, see [`info!`].
+static QUIET: AtomicBool = AtomicBool::new(false);
+/// Whether Ctrl-C was pressed, in which case no new translation is started.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+fn main() -> ExitCode {
+    // NOTE: Returning the error would print its `Debug` representation, which isn't helpful.
+    match run() {
+        Ok(exit_code) => exit_code,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            ExitCode::FAILURE
+        },
+    }
+}
+
+fn run() -> Result<ExitCode> {
+    let Cli { global, command, translate: default_args } = Cli::parse();
+    // Paths are compared a lot (e.g. content directories and Hugo's output), make them all absolute and canonical.
+    let root = fs::canonicalize(&global.root).map_err(|err| Error::CouldNotReadFile(global.root.clone(), err))?;
+    let global = GlobalArgs { root, ..global };
+
+    match command.unwrap_or(Subcommand::Translate(default_args)) {
+        Subcommand::Translate(args) => translate_command(Args { global, ..args }),
+        Subcommand::Check(args) => check_command(Args { global, ..args }),
+        Subcommand::List(args) => list_command(Args { global, ..args }),
+        Subcommand::Config => dump_config(&global),
+        Subcommand::Doctor { format } => doctor(&global, format),
+    }
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let cmd_args = Args::parse();
+fn translate_command(cmd_args: Args) -> Result<ExitCode> {
+    let deadline = cmd_args.timeout.map(|timeout| Instant::now() + timeout);
+    // Status messages would be mixed with the translation in `--stdin` mode, prompts or the JSON summary.
+    let prints_prompts = cmd_args.prompt_only && cmd_args.prompt_dir.is_none();
+    QUIET.store(cmd_args.quiet || cmd_args.stdin || prints_prompts || cmd_args.format == OutputFormat::Json, Ordering::Relaxed);
+
     let translator = translator::auto_detect(&cmd_args)?;
 
-    let hugo_config = {
-        let stdout = hugo(&cmd_args, vec!["config", "--format", "yaml"])?;
+    if cmd_args.stdin {
+        return translate_stdin(&cmd_args, translator.as_ref())
+    }
+
+    // Held until the end of the run. Dry runs, plans and prompts don't write translations.
+    let lock = if cmd_args.no_lock || cmd_args.dry_run || cmd_args.prompt_only || cmd_args.plan_out.is_some() {
+        None
+    } else {
+        Some(RunLock::acquire(&cmd_args.global.root)?)
+    };
 
-        let hugo_config_dto: HugoConfigDTO = serde_yaml::from_str(&stdout)
-            .map_err(Error::Yaml)?;
-        // println!("Found config: {:?}", hugo_config_dto);
-    
-        let hugo_config = HugoConfig::new(hugo_config_dto, cmd_args.root.clone());
-        // println!("Derived config: {:?}", hugo_config);
+    let hugo_config = load_site(&cmd_args)?;
 
-        hugo_config
+    let glossary = Glossary::new(hugo_config.taxonomy_fields.clone());
+    let (mut jobs, plan_summary) = if cmd_args.retry_failed {
+        (failed_jobs(&cmd_args, &hugo_config)?, PlanSummary::default())
+    } else if let Some(plan_path) = &cmd_args.plan_in {
+        (recorded_jobs(&cmd_args, &hugo_config, plan::load(plan_path)?)?, PlanSummary::default())
+    } else {
+        plan_jobs(&cmd_args, &hugo_config, &glossary)?
     };
+    if let Some(lang) = &cmd_args.bootstrap {
+        bootstrap(&mut jobs, lang, &hugo_config, &cmd_args)?;
+    }
+    if cmd_args.interactive {
+        select_languages(&mut jobs)?;
+    }
+    if let Some(sample_size) = cmd_args.sample {
+        sample_jobs(&mut jobs, sample_size, cmd_args.seed);
+    }
+    if let Some(max_files) = cmd_args.max_files {
+        limit_jobs(&mut jobs, max_files);
+    }
+    if let Some(plan_path) = &cmd_args.plan_out {
+        plan::save(plan_path, jobs.iter().map(|job| job.failed_translation(&cmd_args.global.root)).collect())?;
+        info!("Saved {} translation job(s) in <{}>, run them with `--plan-in`.", jobs.len(), plan_path.display());
+        return Ok(ExitCode::SUCCESS)
+    }
+    if cmd_args.prompt_only {
+        return write_prompts(&jobs, translator.as_ref(), &hugo_config, &glossary, &cmd_args).map(|()| ExitCode::SUCCESS)
+    }
+    let job_count = jobs.len();
+    let job_languages: HashSet<_> = jobs.iter().map(|job| job.to_lang.clone()).collect();
 
-    if hugo_config.language_configs.len() < 2 {
-        return Err(Box::new(Error::NoTranslationPossible))
+    delete_orphans(&plan_summary.orphans, &cmd_args)?;
+
+    // Some translators (e.g. the clipboard-based one) can't be used concurrently.
+    let concurrency = cmd_args.jobs.min(translator.max_concurrency()).max(1);
+    let jobs = Mutex::new(jobs.into_iter());
+    let failed: Mutex<Vec<(TranslationJob, Error)>> = Mutex::new(Vec::new());
+    let saved: Mutex<Vec<(TranslationJob, PathBuf, String)>> = Mutex::new(Vec::new());
+    let done = AtomicUsize::new(0);
+    let snapshots = match (&cmd_args.since_hash_file, cmd_args.merge) {
+        (Some(path), true) => {
+            let mut snapshots = SourceSnapshots::load(&cmd_args.global.root, path)?;
+            snapshots.retain_recorded(&SourceHashes::load(&cmd_args.global.root, path)?);
+            Some(snapshots)
+        },
+        _ => None,
+    };
+    // Save failures (and source hashes) of interrupted runs too, so they can be continued.
+    // NOTE: Exiting skips destructors, so the lock is released by the handler itself.
+    let lock_path = lock.as_ref().map(|lock| lock.path().to_path_buf());
+    ctrlc::set_handler(move || {
+        if INTERRUPTED.swap(true, Ordering::Relaxed) {
+            if let Some(lock_path) = &lock_path {
+                let _ = fs::remove_file(lock_path);
+            }
+            std::process::exit(INTERRUPTED_EXIT_CODE.into());
+        }
+        eprintln!("Interrupted, finishing translations in progress (hit [Enter] to stop waiting for a manual translation, or press Ctrl-C again to quit now)…");
+    })?;
+    thread::scope(|scope| {
+        for _ in 0..concurrency {
+            scope.spawn(|| loop {
+                // NOTE: Translations in progress are finished, so no file is left half-written.
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) || INTERRUPTED.load(Ordering::Relaxed) {
+                    break
+                }
+                let Some(job) = jobs.lock().unwrap().next() else { break };
+
+                // Failures don't stop the run, they are reported at the end.
+                match translate(&job, translator.as_ref(), &hugo_config, &glossary, snapshots.as_ref(), &cmd_args) {
+                    Ok((translated_file_path, source)) => saved.lock().unwrap().push((job, translated_file_path, source)),
+                    Err(err) => {
+                        eprintln!("Could not translate <{}> to '{}': {}", job.content_file_path.display(), job.to_lang, err);
+                        failed.lock().unwrap().push((job, err));
+                    },
+                }
+                // Bootstrapping a language takes a while, show how far it went.
+                let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+                if cmd_args.bootstrap.is_some() {
+                    info!("[{}/{}] translation(s) done.", done, job_count);
+                }
+            });
+        }
+    });
+
+    let not_started_jobs: Vec<TranslationJob> = jobs.into_inner().unwrap().collect();
+    let not_started = not_started_jobs.len();
+    let failures = failed.into_inner().unwrap();
+    let failed_translations = failures.iter()
+        .map(|(job, _)| job.failed_translation(&cmd_args.global.root))
+        .collect::<Vec<_>>();
+    let saved = saved.into_inner().unwrap();
+    if !cmd_args.dry_run {
+        let succeeded = saved.iter()
+            .map(|(job, _, _)| job.failed_translation(&cmd_args.global.root))
+            .collect::<Vec<_>>();
+        failures::save(&cmd_args.global.root, &succeeded, &failed_translations)?;
+    }
+    if let (Some(path), false) = (&cmd_args.since_hash_file, cmd_args.dry_run) {
+        save_source_hashes(&cmd_args, path, &plan_summary, &saved)?;
+    }
+    if cmd_args.verify_build && !cmd_args.dry_run && !saved.is_empty() {
+        verify_build(&cmd_args, &saved.iter().map(|(_, path, _)| path.clone()).collect::<Vec<_>>())?;
     }
 
-    let mut files_metadata: Vec<Box<FileMetadata>> = Vec::new();
-    let mut all_translations: HashMap<String, HashMap<String, Box<FileMetadata>>> = HashMap::new();
-    let draft_files = if cmd_args.drafts { vec![] } else { draft_files(&cmd_args)? };
-    for (language_identifier, language_config) in hugo_config.language_configs.iter() {
-        // println!("Finding files in '{}'…", language_config.language_name);
-        let files = find_markdown_files(&language_config.content_dir);
-        // println!("Files found:\n{:?}", files);
+    let stats = translator.stats();
+    if let Some(report_path) = &cmd_args.report {
+        let jobs = saved.iter()
+            .map(|(job, path, _)| (job, if cmd_args.dry_run { Outcome::Planned(path.clone()) } else { Outcome::Saved(path.clone()) }))
+            .chain(failures.iter().map(|(job, err)| (job, Outcome::Failed(err.to_string()))))
+            .chain(not_started_jobs.iter().map(|job| (job, Outcome::NotStarted)))
+            .collect();
+        let report = Report {
+            root: &cmd_args.global.root,
+            generator: translator.generator(),
+            stats,
+            languages: hugo_config.language_configs.keys().map(String::as_str).collect(),
+            translation_groups: &plan_summary.translation_groups,
+            jobs,
+            // NOTE: Loaded after saving, so hashes of this run's translations are listed too.
+            source_hashes: cmd_args.since_hash_file.as_ref()
+                .map(|path| SourceHashes::load(&cmd_args.global.root, path))
+                .transpose()?,
+        };
+        report.save(report_path)?;
+        info!("Report saved in <{}>.", report_path.display());
+    }
+    match cmd_args.format {
+        OutputFormat::Human => if let Some(stats) = stats {
+            info!(
+                "{}: {} API call(s) in {}ms ({}ms on average), {} retry(ies).",
+                translator.generator(), stats.api_calls, stats.total_latency_ms, stats.average_latency_ms(), stats.retries,
+            );
+        },
+        OutputFormat::Json => {
+            let summary = Summary {
+                translated: saved.len(),
+                failed: failed_translations,
+                not_started,
+                generator: translator.generator(),
+                stats,
+            };
+            println!("{}", serde_json::to_string_pretty(&summary)?);
+        },
+    }
 
-        let translatable_files = files
-            .iter()
-            // Mapping to `FileMetadata` has the side effect of filtering out files which do not contain a `translationKey` in their front matter.
-            .flat_map(|path| FileMetadata::try_from(path.to_owned(), language_identifier.clone()))
-            .map(Box::new)
+    if cmd_args.dry_run {
+        info!(
+            "{} file(s) scanned, {} translation job(s) planned across {} language(s), {} unpublished page(s) skipped, {} file(s) already translated.",
+            plan_summary.scanned_files, job_count, job_languages.len(), plan_summary.skipped_unpublished, plan_summary.fully_translated,
+        );
+    }
+
+    let interrupted = INTERRUPTED.load(Ordering::Relaxed);
+    if interrupted {
+        eprintln!("Interrupted: {} translation(s) saved, {} not started (run again to translate them).", saved.len(), not_started);
+    } else if not_started > 0 {
+        eprintln!(
+            "Timed out after {}s: {} translation(s) saved, {} not started (run again to translate them).",
+            cmd_args.timeout.unwrap_or_default().as_secs(), saved.len(), not_started,
+        );
+    }
+    if !failures.is_empty() {
+        eprintln!("{} translation(s) failed:", failures.len());
+        for (job, err) in failures.iter() {
+            eprintln!("- <{}> to '{}': {}", job.content_file_path.display(), job.to_lang, err);
+        }
+    }
+    if interrupted {
+        return Ok(ExitCode::from(INTERRUPTED_EXIT_CODE))
+    }
+    if !failures.is_empty() {
+        return Ok(ExitCode::from(TRANSLATIONS_FAILED_EXIT_CODE))
+    }
+    if not_started > 0 {
+        return Ok(ExitCode::from(TIMED_OUT_EXIT_CODE))
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn load_hugo_config(global_args: &GlobalArgs) -> Result<HugoConfig> {
+    let stdout = hugo(global_args, vec!["config", "--format", "yaml"])?;
+
+    let mut hugo_config_dto: HugoConfigDTO = serde_yaml::from_str(&stdout)?;
+    // println!("Found config: {:?}", hugo_config_dto);
+
+    if let Some(default_content_language) = &global_args.default_content_language {
+        if !hugo_config_dto.languages.contains_key(default_content_language) {
+            return Err(Error::UnknownLanguage(default_content_language.clone()))
+        }
+        hugo_config_dto.default_content_language = default_content_language.clone();
+    }
+
+    let hugo_config = HugoConfig::new(hugo_config_dto, global_args.root.clone());
+    // println!("Derived config: {:?}", hugo_config);
+
+    Ok(hugo_config)
+}
+
+/// Loads the Hugo configuration, checking the site can be translated.
+fn load_site(cmd_args: &Args) -> Result<HugoConfig> {
+    let hugo_config = load_hugo_config(&cmd_args.global)?;
+
+    if hugo_config.language_configs.len() < 2 {
+        let languages = hugo_config.language_configs.iter()
+            .map(|(lang, config)| format!("'{}' (<{}>)", lang, config.content_dir.display()))
             .collect::<Vec<_>>();
+        let languages = if languages.is_empty() { "none".to_string() } else { languages.join(", ") };
+        return Err(Error::NoTranslationPossible(hugo_config.language_configs.len(), languages))
+    }
 
-        for metadata in translatable_files.iter() {
-            all_translations
-                .entry(metadata.clone().translation_key)
-                .or_insert(HashMap::new())
-                .insert(metadata.clone().language_identifier, metadata.to_owned());
+    if let Some(source_lang) = &cmd_args.source_lang {
+        if !hugo_config.language_configs.contains_key(source_lang) {
+            return Err(Error::UnknownLanguage(source_lang.clone()))
         }
+    }
 
-        let translatable_files = translatable_files.into_iter().filter(|p| {
-            if draft_files.contains(&p.path) {
-                println!("Skipping draft page <{}>…", &p.path.display());
-                false
-            } else {
-                true
-            }
-        }).collect::<Vec<_>>();
-        files_metadata.extend(translatable_files);
+    Ok(hugo_config)
+}
+
+fn check_command(cmd_args: Args) -> Result<ExitCode> {
+    QUIET.store(cmd_args.quiet, Ordering::Relaxed);
+
+    let hugo_config = load_site(&cmd_args)?;
+    let glossary = Glossary::new(hugo_config.taxonomy_fields.clone());
+    let (jobs, _) = plan_jobs(&cmd_args, &hugo_config, &glossary)?;
+
+    if jobs.is_empty() {
+        info!("All pages are translated.");
+        return Ok(ExitCode::SUCCESS)
     }
-    // println!("Derived metadata: {:?}", files_metadata);
-    // println!("All translations: {:?}", all_translations);
 
-    let all_languages: HashSet<_> = hugo_config.language_configs.keys().collect();
-    for metadata in files_metadata {
-        let translation_key = metadata.translation_key;
-        let translations = all_translations.get(&translation_key).cloned().unwrap_or_default();
-        let from_lang = metadata.language_identifier;
+    eprintln!("{} translation(s) missing:", jobs.len());
+    for job in jobs.iter() {
+        eprintln!("- <{}> in '{}'", job.source.path.display(), job.to_lang);
+    }
+    Ok(ExitCode::from(TRANSLATIONS_MISSING_EXIT_CODE))
+}
 
-        let already_translated_languages: HashSet<_> = translations.keys().collect();
-        let to_translate: HashSet<_> = all_languages.difference(&already_translated_languages).collect();
+fn list_command(cmd_args: Args) -> Result<ExitCode> {
+    // Status messages would be mixed with the list.
+    QUIET.store(true, Ordering::Relaxed);
 
-        let from_language_config = hugo_config.language_configs
-            .get(&from_lang)
-            .expect("TODO");
+    let hugo_config = load_site(&cmd_args)?;
+    let glossary = Glossary::new(hugo_config.taxonomy_fields.clone());
+    let (jobs, _) = plan_jobs(&cmd_args, &hugo_config, &glossary)?;
+
+    // One missing translation per line: target language and source file.
+    for job in jobs.iter() {
+        println!("{}\t{}", job.to_lang, job.source.path.display());
+    }
 
-        let original_content = fs::read_to_string(&metadata.path)
-            .map_err(|err| Error::CouldNotReadFile(metadata.path.clone(), err))?;
+    Ok(ExitCode::SUCCESS)
+}
 
-        let content_file_path = metadata.path
-            .strip_prefix(from_language_config.content_dir.clone())
-                .expect(&format!("{}", from_language_config.content_dir.display()))
-            .to_path_buf();
+/// Environment variables read by translators, and whether their value must be hidden.
+const ENVIRONMENT_VARIABLES: [(&str, bool); 4] = [
+    ("OPENAI_API_BASE", false),
+    ("OPENAI_API_KEY", true),
+    ("OPENAI_API_MODEL", false),
+    ("OPENAI_CHAT_MODEL", false),
+];
 
-        for to_lang in to_translate {
-            println!("Translating <{}> from '{}' to '{}'…", content_file_path.display(), from_lang, to_lang);
+/// Effective configuration, for the `config` subcommand.
+#[derive(Debug, Serialize)]
+struct DumpedConfig<'a> {
+    args: &'a GlobalArgs,
+    hugo: &'a HugoConfig,
+    environment: IndexMap<&'static str, Option<String>>,
+}
+
+/// Prints the effective configuration (global arguments, Hugo configuration and environment), without secrets.
+fn dump_config(global_args: &GlobalArgs) -> Result<ExitCode> {
+    let hugo_config = load_hugo_config(global_args)?;
+
+    // Translators read `.env` too, but it's fine if it doesn't exist.
+    dotenvy::dotenv().ok();
+    let environment = ENVIRONMENT_VARIABLES.iter()
+        .map(|(name, is_secret)| {
+            let value = env::var(name).ok()
+                .map(|value| if *is_secret { "<redacted>".to_string() } else { value });
+            (*name, value)
+        })
+        .collect();
 
-            let to_language_config = hugo_config.language_configs
-                .get(to_lang.to_owned())
-                .expect("TODO");
+    let config = DumpedConfig { args: global_args, hugo: &hugo_config, environment };
+    print!("{}", serde_yaml::to_string(&config)?);
 
-            let translated_file_path = translator.translate_path(&content_file_path, &from_lang, &to_lang)?;
-            let translated_file_path = to_language_config.content_dir.join(translated_file_path);
+    Ok(ExitCode::SUCCESS)
+}
 
-            let translation = translator.translate_content(&original_content, &from_lang, &to_lang, "hash".to_string())?;
+/// Keeps jobs translating into `lang` only, and prepares its content directory.
+///
+/// NOTE: Interrupted bootstraps can be run again to continue where they stopped: saved translations
+///   have the translation key of their source so they are not missing anymore, and they are written
+///   atomically (see `write_atomically`) so an interruption can't leave a truncated translation behind.
+fn bootstrap(jobs: &mut Vec<TranslationJob>, lang: &String, hugo_config: &HugoConfig, cmd_args: &Args) -> Result<()> {
+    let Some(language_config) = hugo_config.language_configs.get(lang) else {
+        return Err(Error::UnknownLanguage(lang.clone()))
+    };
+    jobs.retain(|job| &job.to_lang == lang);
 
-            println!("Saving '{}' translation of <{}> in <{}>…", to_lang, content_file_path.display(), translated_file_path.display());
-            fs::create_dir_all(translated_file_path.parent().unwrap())?;
-            fs::write(translated_file_path, translation)?;
-        }
+    // Content directories of new languages often don't exist yet.
+    if !language_config.content_dir.exists() && cmd_args.output_dir.is_none() && !cmd_args.dry_run {
+        check_write_path(&language_config.content_dir, cmd_args)?;
+        info!("Creating content directory <{}>…", language_config.content_dir.display());
+        fs::create_dir_all(&language_config.content_dir)?;
     }
+    info!(
+        "Bootstrapping '{}' ({}): {} page(s) to translate{}.",
+        lang, language_config.language_name, jobs.len(),
+        if cmd_args.max_files.is_some() { "" } else { ", use `--max-files` to translate them in batches" },
+    );
 
     Ok(())
 }
 
-#[derive(Debug)]
-enum Error {
-    /// Failed to execute command
-    CommandInvocationFailed(std::io::Error),
-    HugoCommandFailed(String),
-    Yaml(serde_yaml::Error),
-    NoTranslationPossible,
-    FileHasNoName,
-    CouldNotReadFile(PathBuf, std::io::Error),
-    NoFrontMatterFound(PathBuf),
-    FrontMatterParsingFailed(serde_yaml::Error),
+/// Keeps the jobs of the first `max_files` source files (in all languages), so large runs can be split in batches.
+fn limit_jobs(jobs: &mut Vec<TranslationJob>, max_files: usize) {
+    let mut sources: Vec<&PathBuf> = jobs.iter().map(|job| &job.source.path).collect();
+    // NOTE: Jobs are sorted (see `TranslationJob::order_key`), so sources are taken in a stable order.
+    let mut seen = HashSet::new();
+    sources.retain(|path| seen.insert(*path));
+    if sources.len() <= max_files {
+        return
+    }
+    info!("Translating {} of {} page(s), run again to translate the next ones.", max_files, sources.len());
+    let kept: HashSet<PathBuf> = sources[..max_files].iter().map(|path| path.to_path_buf()).collect();
+    jobs.retain(|job| kept.contains(&job.source.path));
+}
+
+/// Asks which languages to translate into, and keeps jobs targeting them.
+/// Does nothing if stdin or stderr is not a terminal (e.g. in CI).
+fn select_languages(jobs: &mut Vec<TranslationJob>) -> Result<()> {
+    if !io::stdin().is_terminal() || !io::stderr().is_terminal() {
+        eprintln!("Warning: Not running in a terminal, translating into all languages.");
+        return Ok(())
+    }
+
+    let mut languages: Vec<String> = jobs.iter().map(|job| job.to_lang.clone()).collect();
+    languages.sort();
+    languages.dedup();
+    if languages.is_empty() {
+        return Ok(())
+    }
+    let items: Vec<String> = languages.iter()
+        .map(|lang| format!("{} ({} page(s))", lang, jobs.iter().filter(|job| &job.to_lang == lang).count()))
+        .collect();
+
+    let selection = dialoguer::MultiSelect::new()
+        .with_prompt("Languages to translate into (space to select, enter to confirm)")
+        .items(&items)
+        .defaults(&vec![true; items.len()])
+        .interact()?;
+    let selected: HashSet<&String> = selection.into_iter().map(|index| &languages[index]).collect();
+    jobs.retain(|job| selected.contains(&job.to_lang));
+
+    Ok(())
 }
 
-impl std::fmt::Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Error: {:?}", self)
+/// Keeps `sample_size` random jobs, for quality spot-checks.
+fn sample_jobs(jobs: &mut Vec<TranslationJob>, sample_size: usize, seed: Option<u64>) {
+    // Always print the seed, so a sample can be reproduced.
+    let seed = seed.unwrap_or_else(rand::random);
+    let mut rng = StdRng::seed_from_u64(seed);
+    jobs.shuffle(&mut rng);
+    jobs.truncate(sample_size);
+
+    info!("Sampled {} translation job(s) (seed {}):", jobs.len(), seed);
+    for job in jobs.iter() {
+        info!("- <{}> to '{}'", job.source.path.display(), job.to_lang);
     }
 }
 
-impl std::error::Error for Error {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        match self {
-            Error::CommandInvocationFailed(err) => Some(err),
-            Error::Yaml(err) => Some(err),
-            Error::CouldNotReadFile(_, err) => Some(err),
-            Error::FrontMatterParsingFailed(err) => Some(err),
-            _ => None,
+/// Result of a `doctor` check.
+#[derive(Debug, Serialize)]
+struct Diagnostic {
+    check: &'static str,
+    ok: bool,
+    message: String,
+    /// How to fix the problem, if the check failed.
+    hint: Option<&'static str>,
+}
+
+impl Diagnostic {
+    fn new(check: &'static str, result: std::result::Result<String, String>, hint: &'static str) -> Self {
+        match result {
+            Ok(message) => Self { check, ok: true, message, hint: None },
+            Err(message) => Self { check, ok: false, message, hint: Some(hint) },
         }
     }
 }
 
-fn find_markdown_files(directory: &PathBuf) -> Vec<PathBuf> {
-    let mut markdown_files = Vec::new();
+/// Checks the environment is ready for translation, and reports every problem at once.
+fn doctor(global_args: &GlobalArgs, format: OutputFormat) -> Result<ExitCode> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
 
-    let entries = WalkDir::new(directory)
-        .into_iter()
-        .filter_map(|e| e.ok());
-    for entry in entries {
-        let path = entry.into_path();
-        if let Some(extension) = path.extension() {
-            if extension == "md" {
-                markdown_files.push(path);
+    let hugo_version = Command::new(&global_args.hugo_bin).arg("version").output()
+        .map_err(|err| err.to_string())
+        .and_then(|output| match output.status.success() {
+            true => Ok(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+            false => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+        });
+    diagnostics.push(Diagnostic::new("hugo", hugo_version, "Install Hugo (https://gohugo.io/installation/) or pass its path with `--hugo-bin`."));
+
+    let hugo_config = load_hugo_config(global_args);
+    let languages = match &hugo_config {
+        Ok(hugo_config) => match hugo_config.language_configs.len() {
+            0 | 1 => Err(format!("{} language(s) with a content directory", hugo_config.language_configs.len())),
+            _ => Ok(hugo_config.language_configs.keys().cloned().collect::<Vec<_>>().join(", ")),
+        },
+        Err(_) => Err("Hugo configuration could not be loaded".to_string()),
+    };
+    diagnostics.push(Diagnostic::new(
+        "config",
+        hugo_config.as_ref().map(|_| format!("<{}>", global_args.root.display())).map_err(|err| err.to_string()),
+        "Run from the website root or pass it with `--root`.",
+    ));
+    diagnostics.push(Diagnostic::new("languages", languages, "Configure at least two languages, each with a `contentDir`."));
+
+    let dotenv = dotenvy::dotenv().map(|path| format!("<{}>", path.display()));
+    let env_var = |name: &str| env::var(name).map(|_| "set".to_string()).map_err(|err| format!("{} ({})", err, name));
+    diagnostics.push(Diagnostic::new(
+        ".env",
+        dotenv.or_else(|err| if err.not_found() { Ok("not found".to_string()) } else { Err(err.to_string()) }),
+        "Fix the syntax of `.env`.",
+    ));
+    diagnostics.push(Diagnostic::new("auto translation", env_var("OPENAI_API_KEY"), "Set `OPENAI_API_KEY` (in the environment or `.env`) to use `--auto`."));
+    diagnostics.push(Diagnostic::new("manual translation", env_var("OPENAI_CHAT_MODEL"), "Set `OPENAI_CHAT_MODEL` (in the environment or `.env`) to translate manually."));
+    diagnostics.push(Diagnostic::new(
+        "clipboard",
+        translator::probe_clipboard().map(|_| "available".to_string()).map_err(|err| err.to_string()),
+        "Manual translation needs a clipboard (e.g. a graphical session), use `--auto` otherwise.",
+    ));
+
+    match format {
+        OutputFormat::Human => for diagnostic in diagnostics.iter() {
+            let status = if diagnostic.ok { "PASS" } else { "FAIL" };
+            println!("[{}] {}: {}", status, diagnostic.check, diagnostic.message);
+            if let Some(hint) = diagnostic.hint {
+                println!("       {}", hint);
             }
+        },
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&diagnostics)?),
+    }
+
+    if diagnostics.iter().all(|diagnostic| diagnostic.ok) {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+/// Records the hashes of the sources of saved translations in `--since-hash-file`,
+/// and the sources themselves with `--merge`.
+fn save_source_hashes(cmd_args: &Args, path: &Path, plan_summary: &PlanSummary, saved: &[(TranslationJob, PathBuf, String)]) -> Result<()> {
+    let mut source_hashes = SourceHashes::load(&cmd_args.global.root, path)?;
+    let mut snapshots = if cmd_args.merge { Some(SourceSnapshots::load(&cmd_args.global.root, path)?) } else { None };
+    let saved_sources = saved.iter()
+        .filter_map(|(job, _, source)| Some((job.source.translation_keys.iter().min()?, &job.to_lang, source)));
+    for (translation_key, lang, source) in plan_summary.unrecorded_sources.iter().map(|(key, lang, source)| (key, lang, source)).chain(saved_sources) {
+        source_hashes.set(translation_key, lang, hashes::source_hash(source));
+        if let Some(snapshots) = snapshots.as_mut() {
+            snapshots.set(translation_key, lang, source.clone());
+        }
+    }
+    if let Some(snapshots) = snapshots {
+        snapshots.save()?;
+    }
+    source_hashes.save()
+}
+
+/// Builds the site (in memory) to make sure saved translations don't break it.
+fn verify_build(cmd_args: &Args, saved: &[PathBuf]) -> Result<()> {
+    info!("Verifying the site builds…");
+    let Err(err) = hugo(&cmd_args.global, vec!["--renderToMemory", "--quiet"]) else {
+        return Ok(())
+    };
+    let Error::HugoCommandFailed(stderr) = &err else {
+        return Err(err)
+    };
+
+    // Hugo mentions the files it failed to process, relative to the site root.
+    let suspects = saved.iter()
+        .filter(|path| {
+            let relative_path = path.strip_prefix(&cmd_args.global.root).unwrap_or(path.as_path());
+            stderr.contains(&relative_path.display().to_string())
+        })
+        .map(|path| format!("<{}>", path.display()))
+        .collect::<Vec<_>>();
+    if suspects.is_empty() {
+        Err(Error::BuildFailed(format!("{} translation(s) were saved, none of which is mentioned in Hugo's output", saved.len())))
+    } else {
+        Err(Error::BuildFailed(format!("probably because of {}", suspects.join(", "))))
+    }
+}
+
+/// Translates a single markdown document read from stdin, bypassing the Hugo site entirely.
+fn translate_stdin(cmd_args: &Args, translator: &dyn Translator) -> Result<ExitCode> {
+    // NOTE: Unwrapping is safe here as clap makes sure both languages are set with `--stdin`.
+    let from_lang = cmd_args.source_lang.as_ref().unwrap();
+    let to_lang = cmd_args.target_lang.as_ref().unwrap();
+
+    let text = io::read_to_string(io::stdin())?;
+    let content = Content { conversation: None, ..Content::new(&text, Path::new("stdin.md")) };
+    let translation = translator.translate_content(&content, from_lang, to_lang, "hash".to_string(), None)?;
+    println!("{}", translation);
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Counts gathered while planning jobs, for the dry run summary.
+#[derive(Debug, Clone, Default)]
+struct PlanSummary {
+    scanned_files: usize,
+    /// Drafts, future and expired pages.
+    skipped_unpublished: usize,
+    /// Files which exist in all languages.
+    fully_translated: usize,
+    /// Generated translations whose source doesn't exist anymore (only with `--sync`).
+    orphans: Vec<PathBuf>,
+    /// Translation key, language and source of existing translations which were not in
+    /// `--since-hash-file` yet (only with `--since-hash-file`).
+    unrecorded_sources: Vec<(String, String, String)>,
+    /// Existing files of each translation group, by translation key then language (for `--report`).
+    translation_groups: BTreeMap<String, BTreeMap<String, PathBuf>>,
+}
+
+/// Finds files to translate and the languages they are missing.
+///
+/// Jobs are returned in a stable order (see `TranslationJob::order_key`), so `--max-files` batches
+/// follow each other and a `--sample` seed always selects the same jobs.
+///
+/// Draft sources are handled as follows:
+///
+/// | Source | Translation exists | Default    | `--drafts` | `--include-drafts-as-drafts` |
+/// | ------ | ------------------ | ---------- | ---------- | ---------------------------- |
+/// | Draft  | No                 | Skipped    | Translated | Translated as a draft        |
+/// | Draft  | Yes (draft or not) | Kept       | Kept       | Kept                         |
+/// | Public | No                 | Translated | Translated | Translated                   |
+/// | Public | Yes (draft or not) | Kept       | Kept       | Kept                         |
+///
+/// Draft translations count as existing (they are being worked on), and a skipped draft
+/// is never chosen as the source of its translation group, so a published translation
+/// is used instead if there is one.
+fn plan_jobs(cmd_args: &Args, hugo_config: &HugoConfig, glossary: &Glossary) -> Result<(Vec<TranslationJob>, PlanSummary)> {
+    let mut summary = PlanSummary::default();
+    let mut files_metadata: Vec<Box<FileMetadata>> = Vec::new();
+    let mut all_translations: HashMap<String, HashMap<String, Box<FileMetadata>>> = HashMap::new();
+    let mut skipped_files: Vec<Error> = Vec::new();
+    let draft_files = if cmd_args.drafts { vec![] } else { unpublished_files(cmd_args, "drafts")? };
+    let future_files = if cmd_args.future { vec![] } else { unpublished_files(cmd_args, "future")? };
+    let expired_files = if cmd_args.expired { vec![] } else { unpublished_files(cmd_args, "expired")? };
+    let content_extensions = if cmd_args.html { vec!["md", "html"] } else { vec!["md"] };
+    let translation_ignore = translation_ignore(&cmd_args.global.root)?;
+    let source_hashes = cmd_args.since_hash_file.as_ref()
+        .map(|path| SourceHashes::load(&cmd_args.global.root, path))
+        .transpose()?;
+    let translation_scope = if cmd_args.scope.is_empty() { &hugo_config.translation_scope } else { &cmd_args.scope };
+    // With `--language-field`, languages can share content directories.
+    let mut seen_files: HashSet<PathBuf> = HashSet::new();
+    if cmd_args.language_field.is_none() {
+        warn_shared_content_dirs(hugo_config);
+    }
+    for (language_identifier, language_config) in hugo_config.language_configs.iter() {
+        // println!("Finding files in '{}'…", language_config.language_name);
+        let (files, walk_errors) = find_content_files(&language_config.content_dir, &content_extensions);
+        skipped_files.extend(walk_errors);
+        // println!("Files found:\n{:?}", files);
+        summary.scanned_files += files.len();
+
+        let mut translatable_files: Vec<Box<FileMetadata>> = Vec::with_capacity(files.len());
+        for path in files {
+            if cmd_args.language_field.is_some() && !seen_files.insert(path.clone()) {
+                continue
+            }
+            // Content directories can be nested (e.g. a catch-all mount), files belong to the most specific one.
+            if cmd_args.language_field.is_none() && hugo_config.content_language(&path) != Some(language_identifier) {
+                continue
+            }
+            // Mapping to `FileMetadata` has the side effect of filtering out files which do not contain a `translationKey` in their front matter.
+            match FileMetadata::try_from(path, language_identifier.clone(), &cmd_args.translation_key_field) {
+                Ok(mut metadata) => {
+                    // Trust the language set in the front matter over the directory the file is in.
+                    let front_matter_lang = cmd_args.language_field.as_ref()
+                        .and_then(|field| metadata.front_matter.get(field.as_str()))
+                        .and_then(serde_yaml::Value::as_str);
+                    if let Some(lang) = front_matter_lang {
+                        if !hugo_config.language_configs.contains_key(lang) {
+                            skipped_files.push(Error::UnknownFileLanguage(metadata.path.clone(), lang.to_string()));
+                            continue
+                        }
+                        metadata.language_identifier = lang.to_string();
+                    }
+                    metadata.is_draft = draft_files.contains(&metadata.path);
+                    translatable_files.push(Box::new(metadata));
+                },
+                Err(err) => skipped_files.push(err),
+            }
+        }
+
+        for metadata in translatable_files.iter() {
+            for translation_key in metadata.translation_keys.iter() {
+                all_translations
+                    .entry(translation_key.clone())
+                    .or_default()
+                    .insert(metadata.language_identifier.clone(), metadata.to_owned());
+            }
+        }
+
+        let translatable_files = translatable_files.into_iter().filter(|p| {
+            let content_file_path = p.path.strip_prefix(&language_config.content_dir).unwrap_or(&p.path);
+            if !translation_scope.is_empty() && !translation_scope.iter().any(|prefix| content_file_path.starts_with(prefix)) {
+                false
+            } else if translation_ignore.matched_path_or_any_parents(content_file_path, false).is_ignore() {
+                info!("Skipping ignored page <{}>…", &p.path.display());
+                false
+            } else if p.is_draft && !cmd_args.include_drafts_as_drafts {
+                info!("Skipping draft page <{}>…", &p.path.display());
+                summary.skipped_unpublished += 1;
+                false
+            } else if future_files.contains(&p.path) {
+                info!("Skipping future page <{}>…", &p.path.display());
+                summary.skipped_unpublished += 1;
+                false
+            } else if expired_files.contains(&p.path) {
+                info!("Skipping expired page <{}>…", &p.path.display());
+                summary.skipped_unpublished += 1;
+                false
+            } else if cmd_args.skip_sections && translator::is_section_page(&p.path) {
+                info!("Skipping section page <{}>…", &p.path.display());
+                false
+            } else {
+                !cmd_args.only_sections || translator::is_section_page(&p.path)
+            }
+        }).collect::<Vec<_>>();
+        files_metadata.extend(translatable_files);
+    }
+    // println!("Derived metadata: {:?}", files_metadata);
+
+    // Encoding and file system problems are easy to miss, always report them.
+    for err in skipped_files.iter().filter(|err| matches!(err, Error::NotUtf8(..) | Error::Walk(..))) {
+        eprintln!("Warning: {}", err);
+    }
+    if !skipped_files.is_empty() {
+        if cmd_args.list_skipped {
+            info!("Skipped {} file(s) which can't be translated:", skipped_files.len());
+            for err in skipped_files.iter() {
+                info!("- {}", err);
+            }
+        } else {
+            info!("Skipped {} file(s) which can't be translated (use `--list-skipped` to list them).", skipped_files.len());
         }
     }
+    // println!("All translations: {:?}", all_translations);
+    warn_near_miss_translation_keys(&all_translations);
+    summary.translation_groups = all_translations.iter()
+        .map(|(translation_key, translations)| {
+            let paths = translations.iter().map(|(lang, metadata)| (lang.clone(), metadata.path.clone())).collect();
+            (translation_key.clone(), paths)
+        })
+        .collect();
+    if cmd_args.sync {
+        summary.orphans = orphan_translations(&all_translations, hugo_config);
+    }
 
-    markdown_files
+    // Existing translations tell how taxonomy terms have been translated so far.
+    for translations in all_translations.values() {
+        for (from_lang, source) in translations.iter() {
+            for (to_lang, translation) in translations.iter().filter(|(lang, _)| *lang != from_lang) {
+                glossary.record(from_lang, to_lang, &source.front_matter, &translation.front_matter);
+            }
+        }
+    }
+
+    let all_languages: HashSet<_> = hugo_config.language_configs.keys().collect();
+    // Translate from `--source-lang` first, then the default content language, then by language weight.
+    let source_priority = |lang: &String| (
+        cmd_args.source_lang.as_ref() != Some(lang),
+        lang != &hugo_config.default_content_language,
+        hugo_config.language_configs.get_index_of(lang),
+    );
+    // Files which were filtered out (e.g. drafts) can't be the source of their translation groups.
+    let possible_sources: HashSet<PathBuf> = files_metadata.iter().map(|metadata| metadata.path.clone()).collect();
+    let mut jobs: Vec<TranslationJob> = Vec::new();
+    for metadata in files_metadata {
+        // Orphans are about to be deleted, don't translate them.
+        if summary.orphans.contains(&metadata.path) {
+            continue
+        }
+
+        // Leave the translation to the best source of the translation groups.
+        let best_source_lang = metadata.translation_keys.iter()
+            .flat_map(|translation_key| all_translations.get(translation_key))
+            .flat_map(|translations| translations.iter())
+            .filter(|(_, translation)| possible_sources.contains(&translation.path))
+            .map(|(lang, _)| lang)
+            .min_by_key(|lang| source_priority(lang));
+        if best_source_lang.is_some_and(|lang| source_priority(lang) < source_priority(&metadata.language_identifier)) {
+            continue
+        }
+
+        // A language is already translated if any of the file's translation groups contains it.
+        let already_translated_languages: HashSet<_> = metadata.translation_keys.iter()
+            .flat_map(|translation_key| all_translations.get(translation_key))
+            .flat_map(|translations| translations.keys())
+            .collect();
+        let to_translate: HashSet<_> = all_languages.difference(&already_translated_languages).collect();
+        if to_translate.is_empty() {
+            summary.fully_translated += 1;
+        }
+
+        for to_lang in to_translate {
+            if metadata.no_translate.as_ref().is_some_and(|no_translate| no_translate.excludes(to_lang)) {
+                info!("Skipping '{}' translation of <{}> (`noTranslate`)…", to_lang, metadata.path.display());
+                continue
+            }
+            jobs.push(TranslationJob::new(metadata.clone(), to_lang.to_string(), hugo_config)?);
+        }
+
+        if cmd_args.stale_by_mtime {
+            for translation in stale_translations(&metadata, &all_translations)? {
+                info!("Translation <{}> is older than its source <{}>…", translation.path.display(), metadata.path.display());
+                let mut job = TranslationJob::new(metadata.clone(), translation.language_identifier.clone(), hugo_config)?;
+                job.existing_translation = Some(translation.path.clone());
+                jobs.push(job);
+            }
+        }
+
+        if let Some(source_hashes) = &source_hashes {
+            let source = read_content_file(&metadata.path)?;
+            let source_hash = hashes::source_hash(&source);
+            let translation_key = metadata.translation_keys.iter().min().expect("Sources have a translation key");
+            for translation in generated_translations(&metadata, &all_translations) {
+                let lang = &translation.language_identifier;
+                match source_hashes.get(translation_key, lang) {
+                    Some(hash) if hash != &source_hash => {
+                        if jobs.iter().any(|job| job.existing_translation.as_ref() == Some(&translation.path)) {
+                            continue
+                        }
+                        info!("Source of <{}> changed since it was translated…", translation.path.display());
+                        let mut job = TranslationJob::new(metadata.clone(), lang.clone(), hugo_config)?;
+                        job.existing_translation = Some(translation.path.clone());
+                        jobs.push(job);
+                    },
+                    Some(_) => {},
+                    // Assume translations made before hashes were recorded are up to date.
+                    None => summary.unrecorded_sources.push((translation_key.clone(), lang.clone(), source.clone())),
+                }
+            }
+        }
+    }
+
+    // Files are found and grouped in no particular order, make runs (and plans) reproducible.
+    jobs.sort_by(|job1, job2| job1.order_key().cmp(&job2.order_key()));
+
+    Ok((jobs, summary))
+}
+
+/// Generated translations (which have a `translator` field) of `source` which were modified before it.
+///
+/// NOTE: Modification times are less reliable than content hashes (e.g. `git clone` or copies reset them),
+///   but don't require recording anything. Translations written by hand are never considered stale.
+fn stale_translations<'a>(
+    source: &FileMetadata,
+    all_translations: &'a HashMap<String, HashMap<String, Box<FileMetadata>>>,
+) -> Result<Vec<&'a FileMetadata>> {
+    let modified = |path: &PathBuf| fs::metadata(path).and_then(|metadata| metadata.modified())
+        .map_err(|err| Error::CouldNotReadFile(path.clone(), err));
+    let source_modified = modified(&source.path)?;
+
+    let mut stale: Vec<&FileMetadata> = Vec::new();
+    for translation in generated_translations(source, all_translations) {
+        if modified(&translation.path)? < source_modified {
+            stale.push(translation);
+        }
+    }
+
+    Ok(stale)
+}
+
+/// Generated translations (which have a `translator` field) of `source`.
+fn generated_translations<'a>(
+    source: &FileMetadata,
+    all_translations: &'a HashMap<String, HashMap<String, Box<FileMetadata>>>,
+) -> Vec<&'a FileMetadata> {
+    let mut generated: Vec<&FileMetadata> = Vec::new();
+    let translations = source.translation_keys.iter()
+        .flat_map(|translation_key| all_translations.get(translation_key))
+        .flat_map(|translations| translations.values())
+        .filter(|translation| translation.language_identifier != source.language_identifier)
+        .filter(|translation| translation.front_matter.contains_key("translator"));
+    for translation in translations {
+        // Files belonging to multiple groups are seen once per group.
+        if !generated.iter().any(|t| t.path == translation.path) {
+            generated.push(translation);
+        }
+    }
+    generated
+}
+
+/// Generated translations (which have a `translator` field) in translation groups without a file
+/// in the default content language.
+///
+/// NOTE: Files without a translation key are not part of any group, so they are never considered orphans.
+///   Neither are files written by hand (e.g. pages which only exist in one language).
+fn orphan_translations(all_translations: &HashMap<String, HashMap<String, Box<FileMetadata>>>, hugo_config: &HugoConfig) -> Vec<PathBuf> {
+    let mut orphans: Vec<PathBuf> = all_translations.values()
+        .filter(|translations| !translations.contains_key(&hugo_config.default_content_language))
+        .flat_map(|translations| translations.values())
+        .filter(|metadata| metadata.front_matter.contains_key("translator"))
+        .map(|metadata| metadata.path.clone())
+        .collect();
+    orphans.sort();
+    orphans.dedup();
+
+    // A file belonging to multiple groups is only orphan if all its groups are.
+    orphans.retain(|path| !all_translations.values()
+        .filter(|translations| translations.values().any(|metadata| &metadata.path == path))
+        .any(|translations| translations.contains_key(&hugo_config.default_content_language)));
+
+    orphans
+}
+
+/// Deletes orphan translations (see `orphan_translations`).
+///
+/// NOTE: The site is left as-is with `--output-dir`, only translations previously written there are deleted.
+fn delete_orphans(orphans: &[PathBuf], cmd_args: &Args) -> Result<()> {
+    for orphan in orphans {
+        let orphan = match &cmd_args.output_dir {
+            Some(output_dir) => match orphan.strip_prefix(&cmd_args.global.root) {
+                Ok(relative_path) => output_dir.join(relative_path),
+                Err(_) => continue,
+            },
+            None => orphan.clone(),
+        };
+        if !orphan.exists() {
+            continue
+        }
+        check_write_path(&orphan, cmd_args)?;
+
+        if cmd_args.dry_run {
+            info!("Would delete orphan translation <{}>…", orphan.display());
+        } else {
+            info!("Deleting orphan translation <{}>…", orphan.display());
+            fs::remove_file(&orphan)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Warns about languages sharing a content directory, as their files can only be attributed to one of them.
+fn warn_shared_content_dirs(hugo_config: &HugoConfig) {
+    let mut languages_by_dir: IndexMap<&PathBuf, Vec<&String>> = IndexMap::new();
+    for (lang, config) in hugo_config.language_configs.iter() {
+        languages_by_dir.entry(&config.content_dir).or_default().push(lang);
+    }
+    for (content_dir, languages) in languages_by_dir.iter().filter(|(_, languages)| languages.len() > 1) {
+        eprintln!(
+            "Warning: Languages {} share the content directory <{}>, its files are considered '{}' (use `--language-field` to read languages from front matter).",
+            languages.iter().map(|lang| format!("'{}'", lang)).collect::<Vec<_>>().join(", "), content_dir.display(), languages[0],
+        );
+    }
+}
+
+/// Warns about translation keys which differ only by case or whitespace, as they are most likely typos
+/// which make files look untranslated (and translated again).
+fn warn_near_miss_translation_keys(all_translations: &HashMap<String, HashMap<String, Box<FileMetadata>>>) {
+    let normalize = |key: &str| key.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+
+    let mut similar_keys: HashMap<String, Vec<&String>> = HashMap::new();
+    for translation_key in all_translations.keys() {
+        similar_keys.entry(normalize(translation_key)).or_default().push(translation_key);
+    }
+
+    for keys in similar_keys.values_mut().filter(|keys| keys.len() > 1) {
+        keys.sort();
+        eprintln!("Warning: translation keys {} only differ by case or whitespace:", keys.iter().map(|key| format!("{:?}", key)).collect::<Vec<_>>().join(", "));
+        for key in keys.iter() {
+            let mut files = all_translations[*key].values().map(|metadata| &metadata.path).collect::<Vec<_>>();
+            files.sort();
+            for path in files {
+                eprintln!("- {:?}: <{}>", key, path.display());
+            }
+        }
+    }
+}
+
+/// File, at the root of the website, listing content files which must not be translated.
+const TRANSLATION_IGNORE_FILE_NAME: &str = ".translationignore";
+
+/// Patterns of `.translationignore` (in gitignore syntax), matched against paths relative to content directories.
+///
+/// NOTE: Ignored files are not used as translation sources, but still count as existing translations.
+///   Patterns apply before any other filter (e.g. `--drafts`).
+fn translation_ignore(root: &Path) -> Result<Gitignore> {
+    let path = root.join(TRANSLATION_IGNORE_FILE_NAME);
+    // An empty root makes patterns relative to content directories instead of the website root.
+    let mut builder = GitignoreBuilder::new("");
+    if path.exists() {
+        if let Some(err) = builder.add(&path) {
+            return Err(err.into())
+        }
+    }
+    Ok(builder.build()?)
+}
+
+/// Jobs which failed during the previous run.
+fn failed_jobs(cmd_args: &Args, hugo_config: &HugoConfig) -> Result<Vec<TranslationJob>> {
+    let jobs = recorded_jobs(cmd_args, hugo_config, failures::load(&cmd_args.global.root)?)?;
+
+    if jobs.is_empty() {
+        info!("No failed translation to retry.");
+    }
+
+    Ok(jobs)
+}
+
+/// Jobs recorded in a file (failures or a plan).
+fn recorded_jobs(cmd_args: &Args, hugo_config: &HugoConfig, records: Vec<FailedTranslation>) -> Result<Vec<TranslationJob>> {
+    let mut jobs: Vec<TranslationJob> = Vec::new();
+    let draft_files = if cmd_args.include_drafts_as_drafts { unpublished_files(cmd_args, "drafts")? } else { vec![] };
+    for failure in records {
+        let job = FileMetadata::try_from(cmd_args.global.root.join(&failure.source), failure.from_lang, &cmd_args.translation_key_field)
+            .map(|metadata| FileMetadata { is_draft: draft_files.contains(&metadata.path), ..metadata })
+            .and_then(|metadata| TranslationJob::new(Box::new(metadata), failure.to_lang, hugo_config))
+            .map(|job| TranslationJob {
+                existing_translation: failure.existing_translation.map(|path| cmd_args.global.root.join(path)),
+                ..job
+            });
+        match job {
+            Ok(job) => jobs.push(job),
+            Err(err) => eprintln!("Can't translate <{}> again: {}", failure.source.display(), err),
+        }
+    }
+
+    Ok(jobs)
+}
+
+/// Parses a duration made of a number and an optional unit (`s`, `m` or `h`).
+fn parse_duration(value: &str) -> std::result::Result<Duration, String> {
+    let (number, unit_seconds) = match value.trim() {
+        value if value.ends_with('h') => (&value[..value.len() - 1], 3600),
+        value if value.ends_with('m') => (&value[..value.len() - 1], 60),
+        value if value.ends_with('s') => (&value[..value.len() - 1], 1),
+        value => (value, 1),
+    };
+    let number: u64 = number.trim().parse()
+        .map_err(|_| format!("Invalid duration '{}', expected e.g. `90s`, `20m` or `1h`", value))?;
+    Ok(Duration::from_secs(number * unit_seconds))
+}
+
+/// Renders `--translator-template`.
+fn render_translator_template(translator_template: &str, generator: &str) -> String {
+    translator_template
+        .replace("{tool}", env!("CARGO_PKG_NAME"))
+        .replace("{version}", env!("CARGO_PKG_VERSION"))
+        .replace("{date}", &today())
+        .replace("{generator}", generator)
+}
+
+/// Current UTC date (`YYYY-MM-DD`).
+fn today() -> String {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+    // Convert days since 1970-01-01 to a civil date (see http://howardhinnant.github.io/date_algorithms.html#civil_from_days).
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Renders `--path-template` for a content file, making sure it stays in the target content directory.
+fn render_path_template(path_template: &str, content_file_path: &Path, from_lang: &str, to_lang: &str) -> Result<PathBuf> {
+    let dir = content_file_path.parent().unwrap_or(Path::new("")).display().to_string();
+    let slug = content_file_path.file_stem().ok_or(Error::FileHasNoName)?.to_string_lossy();
+    let ext = content_file_path.extension().unwrap_or_default().to_string_lossy();
+    let rendered = path_template
+        .replace("{lang}", to_lang)
+        .replace("{from_lang}", from_lang)
+        .replace("{dir}", &dir)
+        .replace("{slug}", &slug)
+        .replace("{ext}", &ext);
+
+    // NOTE: `{dir}` is empty for files at the root of the content directory, which would make `{dir}/…` absolute.
+    let path = PathBuf::from(rendered.trim_start_matches('/'));
+    if path.components().any(|component| !matches!(component, Component::Normal(_) | Component::CurDir)) {
+        return Err(Error::InvalidPathTemplate(path.display().to_string()))
+    }
+
+    Ok(path)
+}
+
+/// Makes sure nothing is written outside the site root (or `--output-dir`), whatever content directories
+/// are configured or translators answer, unless `--allow-writes-outside-root` is set.
+fn check_write_path(path: &Path, cmd_args: &Args) -> Result<()> {
+    let allowed_dir = cmd_args.output_dir.as_deref().unwrap_or(&cmd_args.global.root);
+    if cmd_args.allow_writes_outside_root || normalize_path(path).starts_with(normalize_path(allowed_dir)) {
+        return Ok(())
+    }
+    Err(Error::WriteOutsideRoot(path.to_path_buf(), allowed_dir.to_path_buf()))
+}
+
+/// Resolves `.` and `..` components without accessing the file system, as paths to write may not exist yet.
+///
+/// NOTE: Symbolic links are not resolved, content directories already are (see `resolve_path`).
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => { normalized.pop(); },
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Transforms every component of a path into a URL-safe slug, preserving the directory structure and extension.
+fn slugify_path(path: &Path) -> PathBuf {
+    let slugify = |name: &str| match name.strip_prefix('_') {
+        // Keep Hugo's leading underscore (e.g. `_index.md`).
+        Some(name) => format!("_{}", slug::slugify(name)),
+        None => slug::slugify(name),
+    };
+
+    let mut slugified_path = PathBuf::new();
+    if let Some(parent) = path.parent() {
+        for component in parent.components() {
+            match component {
+                Component::Normal(name) => slugified_path.push(slugify(&name.to_string_lossy())),
+                component => slugified_path.push(component),
+            }
+        }
+    }
+    if let Some(stem) = path.file_stem() {
+        let mut file_name = slugify(&stem.to_string_lossy());
+        if let Some(extension) = path.extension() {
+            file_name.push('.');
+            file_name.push_str(&extension.to_string_lossy());
+        }
+        slugified_path.push(file_name);
+    }
+
+    slugified_path
+}
+
+/// Checks a translation is still a valid Hugo page belonging to the same translation groups as its source.
+///
+/// On failure, returns an instruction which can be sent back to the translator so it fixes its output.
+fn validate_translation(translation: &str, source: &FileMetadata, translation_key_field: &str) -> Result<(), String> {
+    let translation_keys = source.translation_keys.iter()
+        .map(|key| format!("`{}`", key))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    match FrontMatter::parse(translation, &source.path, translation_key_field) {
+        Ok(FrontMatter { translation_key: Some(keys), .. }) if HashSet::<String>::from(keys.clone()) == source.translation_keys => Ok(()),
+        Ok(_) => Err(format!("Your previous output was missing the `{}` field. Here it is again, it must be kept unchanged: {}.", translation_key_field, translation_keys)),
+        Err(Error::FrontMatterParsingFailed(_, err)) => Err(format!("The front matter of your previous output was not valid YAML ({}). Make sure it is.", err)),
+        Err(Error::InvalidFrontMatter(_, err)) => Err(format!("The front matter of your previous output was not valid ({}). Make sure it is.", err)),
+        Err(Error::DuplicateFrontMatterKey(_, key)) => Err(format!("The front matter of your previous output defined `{}` multiple times. Define each field once.", key)),
+        Err(_) => Err("Your previous output was missing the front matter. Keep the front matter, delimited as in the source, at the beginning of the file.".to_string()),
+    }
+}
+
+/// Translation of a content file into one language.
+#[derive(Debug, Clone)]
+struct TranslationJob {
+    source: Box<FileMetadata>,
+    /// Path of the source file, relative to its language's content directory.
+    content_file_path: PathBuf,
+    to_lang: String,
+    /// Translation to overwrite, when translating it again.
+    existing_translation: Option<PathBuf>,
+}
+
+impl TranslationJob {
+    fn new(source: Box<FileMetadata>, to_lang: String, hugo_config: &HugoConfig) -> Result<Self> {
+        let from_language_config = hugo_config.language_configs
+            .get(&source.language_identifier)
+            .ok_or(Error::UnknownLanguage(source.language_identifier.clone()))?;
+        if !hugo_config.language_configs.contains_key(&to_lang) {
+            return Err(Error::UnknownLanguage(to_lang))
+        }
+
+        // NOTE: With `--language-field`, a file can be in the content directory of another language.
+        let content_file_path = source.path
+            .strip_prefix(from_language_config.content_dir.clone())
+            .ok()
+            .or_else(|| hugo_config.language_configs.values().find_map(|config| source.path.strip_prefix(&config.content_dir).ok()))
+            .ok_or_else(|| Error::NotInContentDir(source.path.clone()))?
+            .to_path_buf();
+
+        Ok(Self { source, content_file_path, to_lang, existing_translation: None })
+    }
+
+    /// Translation key (the first one alphabetically), target language, then source path.
+    fn order_key(&self) -> (Option<&String>, &String, &PathBuf) {
+        (self.source.translation_keys.iter().min(), &self.to_lang, &self.source.path)
+    }
+
+    fn failed_translation(&self, root: &Path) -> FailedTranslation {
+        FailedTranslation {
+            source: self.source.path.strip_prefix(root).unwrap_or(&self.source.path).to_path_buf(),
+            from_lang: self.source.language_identifier.clone(),
+            to_lang: self.to_lang.clone(),
+            existing_translation: self.existing_translation.as_ref()
+                .map(|path| path.strip_prefix(root).unwrap_or(path).to_path_buf()),
+        }
+    }
+}
+
+/// Translates a job, returning where the translation was saved and the source it was made from.
+fn translate(
+    job: &TranslationJob,
+    translator: &dyn Translator,
+    hugo_config: &HugoConfig,
+    glossary: &Glossary,
+    snapshots: Option<&SourceSnapshots>,
+    cmd_args: &Args,
+) -> Result<(PathBuf, String)> {
+    let TranslationJob { source, content_file_path, to_lang, existing_translation } = job;
+    let from_lang = &source.language_identifier;
+
+    info!("Translating <{}> from '{}' to '{}'…", content_file_path.display(), from_lang, to_lang);
+
+    // NOTE: The source is returned to record what was translated, it could change during the run.
+    let original_content = read_content_file(&source.path)?;
+
+    let to_language_config = hugo_config.language_configs
+        .get(to_lang)
+        .ok_or(Error::UnknownLanguage(to_lang.clone()))?;
+
+    let translated_file_path = match &cmd_args.path_template {
+        // Stale translations are translated again in place.
+        // NOTE: With `--language-field`, translations can be outside their language's content directory.
+        _ if existing_translation.is_some() => {
+            let existing_translation = existing_translation.as_ref().unwrap();
+            existing_translation.strip_prefix(&to_language_config.content_dir)
+                .map_err(|_| Error::NotInLanguageContentDir(existing_translation.clone(), to_lang.clone()))?
+                .to_path_buf()
+        },
+        Some(path_template) => render_path_template(path_template, content_file_path, from_lang, to_lang)?,
+        None => {
+            let translated_file_path = translator.translate_path(content_file_path, from_lang, to_lang);
+            delay(cmd_args);
+            translated_file_path?
+        },
+    };
+    let translated_file_path = if cmd_args.slugify_paths && existing_translation.is_none() { slugify_path(&translated_file_path) } else { translated_file_path };
+    let target_content_dir = match &cmd_args.output_dir {
+        // Mirror the content structure (language content directory + content path) under the output directory.
+        Some(output_dir) => output_dir.join(
+            to_language_config.content_dir.strip_prefix(&cmd_args.global.root)
+                .unwrap_or(Path::new(to_lang.as_str()))
+        ),
+        None => to_language_config.content_dir.clone(),
+    };
+    let translated_file_path = target_content_dir.join(translated_file_path);
+    // Languages can share a content directory (see `--language-field`), never overwrite the source.
+    if translated_file_path == source.path {
+        return Err(Error::InvalidTranslation(source.path.clone(), "the translated path is the path of the source".to_string()))
+    }
+    check_write_path(&translated_file_path, cmd_args)?;
+
+    if cmd_args.paths_only {
+        return save_path_only(job, &original_content, translated_file_path, cmd_args).map(|path| (path, original_content))
+    }
+
+    let source_front_matter = FrontMatter::parse(&original_content, &source.path, &cmd_args.translation_key_field)?;
+    let glossary_instructions = glossary.instructions(from_lang, to_lang, &source_front_matter.fields);
+    // NOTE: Files belonging to multiple groups use the first one (alphabetically) as conversation.
+    let conversation = source.translation_keys.iter().min().filter(|_| cmd_args.threads_per_key);
+    let length_limits = length_limit_instructions(&source_front_matter.fields, cmd_args);
+    let content = Content {
+        glossary: glossary_instructions.as_deref(),
+        length_limits: length_limits.as_deref(),
+        conversation: conversation.map(String::as_str),
+        translation_key: source.translation_keys.iter().min().map(String::as_str),
+        right_to_left: to_language_config.is_right_to_left(),
+        from_lang_name: hugo_config.language_name(from_lang),
+        to_lang_name: hugo_config.language_name(to_lang),
+        ..Content::new(&original_content, &source.path)
+    };
+    let merged = match (snapshots, existing_translation) {
+        (Some(snapshots), Some(existing_translation)) => merge_translation(job, existing_translation, &original_content, snapshots, &content, translator, cmd_args)?,
+        _ => None,
+    };
+    let translation = if let Some(merged) = merged {
+        merged
+    } else if cmd_args.front_matter_only {
+        translate_front_matter_fields(&original_content, source, &source_front_matter, translator, to_lang, cmd_args)?
+    } else if cmd_args.body_only {
+        // Keep the front matter byte for byte, only the body is sent to the translator.
+        let (front_matter, body) = front_matter::split_raw(&original_content)
+            .ok_or(Error::NoFrontMatterFound(source.path.clone()))?;
+        let content = Content { text: body, body_only: true, translation_key: None, ..content };
+        let translated_body = translator.translate_content(&content, from_lang, to_lang, "hash".to_string(), None);
+        delay(cmd_args);
+        format!("{}{}", front_matter, translated_body?)
+    } else {
+        let mut correction: Option<String> = None;
+        let mut retries = 0;
+        loop {
+            let translation = translator.translate_content(&content, from_lang, to_lang, "hash".to_string(), correction.as_deref());
+            delay(cmd_args);
+            let translation = translation?;
+
+            // Dry runs don't produce real translations.
+            if cmd_args.dry_run {
+                break translation
+            }
+
+            match validate_translation(&translation, source, &cmd_args.translation_key_field) {
+                Ok(()) => break translation,
+                Err(reason) if retries < cmd_args.max_retries => {
+                    retries += 1;
+                    eprintln!("Invalid '{}' translation of <{}>, retrying ({}/{})…", to_lang, content_file_path.display(), retries, cmd_args.max_retries);
+                    correction = Some(reason);
+                },
+                Err(reason) => return Err(Error::InvalidTranslation(source.path.clone(), reason)),
+            }
+        }
+    };
+
+    if cmd_args.dry_run {
+        info!("Would save '{}' translation of <{}> in <{}>…", to_lang, content_file_path.display(), translated_file_path.display());
+        return Ok((translated_file_path, original_content))
+    }
+
+    let list_fields = translate_list_fields(&source.path, &source_front_matter.fields, translator, from_lang, to_lang, cmd_args)?;
+
+    // Don't rely on the translator for fields the tool is responsible for (e.g. aliases).
+    let mut duplicated_aliases = vec![];
+    let translation = front_matter::rewrite(&translation, &translated_file_path, &cmd_args.translation_key_field, source_front_matter.format, |fields| {
+        duplicated_aliases = front_matter::apply_aliases(fields, &source_front_matter.fields, cmd_args.aliases, to_lang);
+        for (field, items) in list_fields {
+            fields.insert(serde_yaml::Value::from(field), serde_yaml::Value::from(items));
+        }
+        glossary.record(from_lang, to_lang, &source_front_matter.fields, fields);
+        // Translators only add the `translator` field when they see the front matter.
+        let forced_translator_template = if cmd_args.front_matter_only || cmd_args.body_only { Some("{generator}") } else { None };
+        if let Some(translator_template) = cmd_args.translator_template.as_deref().or(forced_translator_template) {
+            // Keep the generator the translation mentions if any, it can be a fallback model.
+            let generator = fields.get("translator").filter(|_| forced_translator_template.is_none())
+                .and_then(serde_yaml::Value::as_str)
+                .unwrap_or(translator.generator())
+                .to_string();
+            let translator_value = render_translator_template(translator_template, &generator);
+            fields.insert(serde_yaml::Value::from("translator"), serde_yaml::Value::from(translator_value));
+        }
+        if let Some(language_field) = cmd_args.language_field.as_ref().filter(|field| source.front_matter.contains_key(field.as_str())) {
+            fields.insert(serde_yaml::Value::from(language_field.as_str()), serde_yaml::Value::from(to_lang.as_str()));
+        }
+        // Don't rely on the translator to keep drafts unpublished.
+        if cmd_args.include_drafts_as_drafts && source.is_draft {
+            fields.insert(serde_yaml::Value::from("draft"), serde_yaml::Value::from(true));
+        }
+    })?;
+    if let Ok(front_matter) = FrontMatter::parse(&translation, &translated_file_path, &cmd_args.translation_key_field) {
+        warn_length_limits(&front_matter.fields, &translated_file_path, cmd_args);
+    }
+    if !duplicated_aliases.is_empty() {
+        eprintln!("Warning: '{}' translation of <{}> has the same aliases as its source ({}), use `--aliases strip` or `--aliases namespace` to avoid conflicting redirects.", to_lang, content_file_path.display(), duplicated_aliases.join(", "));
+    }
+
+    let translation = if cmd_args.review {
+        review(translation, &translated_file_path)?
+    } else {
+        translation
+    };
+
+    info!("Saving '{}' translation of <{}> in <{}>…", to_lang, content_file_path.display(), translated_file_path.display());
+    write_atomically(&translated_file_path, translation)?;
+
+    Ok((translated_file_path, original_content))
+}
+
+/// Updates `existing_translation` with the paragraphs which changed in its source since it was translated
+/// (`--merge`), or returns `None` if it has to be translated entirely.
+fn merge_translation(
+    job: &TranslationJob,
+    existing_translation: &PathBuf,
+    original_content: &str,
+    snapshots: &SourceSnapshots,
+    content: &Content,
+    translator: &dyn Translator,
+    cmd_args: &Args,
+) -> Result<Option<String>> {
+    let TranslationJob { source, to_lang, .. } = job;
+    let from_lang = &source.language_identifier;
+    let recorded = source.translation_keys.iter().min().and_then(|translation_key| snapshots.get(translation_key, to_lang));
+    let Some(previous_source) = recorded else {
+        info!("No previous version of <{}> was recorded, translating <{}> entirely…", source.path.display(), existing_translation.display());
+        return Ok(None)
+    };
+    let translation = read_content_file(existing_translation)?;
+    let (Some((previous_front_matter, previous_body)), Some((front_matter, body)), Some((translation_front_matter, translation_body))) =
+        (front_matter::split_raw(previous_source), front_matter::split_raw(original_content), front_matter::split_raw(&translation))
+    else {
+        return Ok(None)
+    };
+    // NOTE: Front matter fields are not aligned with their translation like paragraphs are.
+    if previous_front_matter != front_matter {
+        info!("Front matter of <{}> changed, translating <{}> entirely…", source.path.display(), existing_translation.display());
+        return Ok(None)
+    }
+
+    let merged_body = merge::merge_paragraphs(previous_body, body, translation_body, |changed| {
+        let content = Content { text: changed, body_only: true, translation_key: None, ..*content };
+        let translated = translator.translate_content(&content, from_lang, to_lang, "hash".to_string(), None);
+        delay(cmd_args);
+        translated
+    })?;
+    let Some(merged_body) = merged_body else {
+        info!("Paragraphs of <{}> don't match the ones of its source, translating it entirely…", existing_translation.display());
+        return Ok(None)
+    };
+
+    Ok(Some(format!("{}{}", translation_front_matter, merged_body)))
+}
+
+/// Writes the prompts of all jobs to stdout, or to files in `--prompt-dir`, without translating anything.
+fn write_prompts(
+    jobs: &[TranslationJob],
+    translator: &dyn Translator,
+    hugo_config: &HugoConfig,
+    glossary: &Glossary,
+    cmd_args: &Args,
+) -> Result<()> {
+    for job in jobs {
+        let TranslationJob { source, content_file_path, to_lang, existing_translation } = job;
+        let from_lang = &source.language_identifier;
+
+        let original_content = read_content_file(&source.path)?;
+        let source_front_matter = FrontMatter::parse(&original_content, &source.path, &cmd_args.translation_key_field)?;
+        let glossary_instructions = glossary.instructions(from_lang, to_lang, &source_front_matter.fields);
+        let to_language_config = hugo_config.language_configs
+            .get(to_lang)
+            .ok_or(Error::UnknownLanguage(to_lang.clone()))?;
+        let content = Content {
+            glossary: glossary_instructions.as_deref(),
+            right_to_left: to_language_config.is_right_to_left(),
+            from_lang_name: hugo_config.language_name(from_lang),
+            to_lang_name: hugo_config.language_name(to_lang),
+            ..Content::new(&original_content, &source.path)
+        };
+
+        let mut prompts = vec![];
+        // Paths are not translated by translators when they are known in advance.
+        if cmd_args.path_template.is_none() && existing_translation.is_none() {
+            prompts.push(translator.path_translate_prompt(content_file_path, from_lang, to_lang));
+        }
+        prompts.push(translator.content_translate_prompt(&content, from_lang, to_lang, "hash".to_string(), None));
+        let prompts = prompts.join("\n\n---8<---\n\n");
+
+        match &cmd_args.prompt_dir {
+            Some(prompt_dir) => {
+                let prompt_file_path = prompt_dir.join(to_lang).join(format!("{}.txt", content_file_path.display()));
+                fs::create_dir_all(prompt_file_path.parent().unwrap())?;
+                fs::write(&prompt_file_path, prompts)?;
+                info!("Wrote '{}' prompts of <{}> in <{}>.", to_lang, content_file_path.display(), prompt_file_path.display());
+            },
+            None => println!("===== <{}> from '{}' to '{}' =====\n\n{}\n", content_file_path.display(), from_lang, to_lang, prompts),
+        }
+    }
+
+    Ok(())
+}
+
+/// Reports the translated path of a job (`--paths-only`), copying the source there with `--copy-source`.
+fn save_path_only(job: &TranslationJob, original_content: &str, translated_file_path: PathBuf, cmd_args: &Args) -> Result<PathBuf> {
+    let TranslationJob { content_file_path, to_lang, .. } = job;
+    if !cmd_args.copy_source || cmd_args.dry_run {
+        info!("'{}' translation of <{}> goes in <{}>.", to_lang, content_file_path.display(), translated_file_path.display());
+        return Ok(translated_file_path)
+    }
+
+    // Never overwrite a translation someone may have started.
+    if translated_file_path.exists() {
+        eprintln!("Warning: <{}> already exists, not copying <{}> there.", translated_file_path.display(), content_file_path.display());
+        return Ok(translated_file_path)
+    }
+    info!("Copying <{}> to <{}> for its '{}' translation…", content_file_path.display(), translated_file_path.display(), to_lang);
+    write_atomically(&translated_file_path, original_content)?;
+
+    Ok(translated_file_path)
+}
+
+/// Writes a file (and its parent directories) so it either has its previous content or `content`,
+/// even if the run is interrupted, by writing a temporary file next to it then renaming it.
+///
+/// NOTE: The temporary file is hidden and has no content extension, so it's never listed as content.
+fn write_atomically(path: &Path, content: impl AsRef<[u8]>) -> Result<()> {
+    let parent = path.parent().unwrap();
+    fs::create_dir_all(parent)?;
+    let file_name = path.file_name().ok_or(Error::FileHasNoName)?.to_string_lossy();
+    let (temporary_path, mut temporary_file) = create_temporary_file(parent, &file_name, "rhesus-tmp")?;
+    let written = temporary_file.write_all(content.as_ref());
+    drop(temporary_file);
+    if let Err(err) = written.and_then(|()| fs::rename(&temporary_path, path)) {
+        let _ = fs::remove_file(&temporary_path);
+        return Err(err.into())
+    }
+
+    Ok(())
+}
+
+/// Prompt instructions for `--max-length` fields the source has, if any.
+fn length_limit_instructions(source_fields: &Mapping, cmd_args: &Args) -> Option<String> {
+    let limits: Vec<String> = cmd_args.max_length.iter()
+        .filter(|(field, _)| source_fields.get(field.as_str()).is_some_and(serde_yaml::Value::is_string))
+        .map(|(field, max_length)| format!("`{}` must be at most {} characters long", field, max_length))
+        .collect();
+    if limits.is_empty() {
+        return None
+    }
+    Some(format!("Keep translated front matter values short: {}.", limits.join(", ")))
+}
+
+/// Warns about `--max-length` fields which are too long in a translation.
+///
+/// NOTE: They are not truncated, as it would likely cut a sentence.
+fn warn_length_limits(fields: &Mapping, translated_file_path: &Path, cmd_args: &Args) {
+    for (field, max_length) in cmd_args.max_length.iter() {
+        let Some(value) = fields.get(field.as_str()).and_then(serde_yaml::Value::as_str) else { continue };
+        let length = value.chars().count();
+        if length > *max_length {
+            eprintln!("Warning: `{}` of <{}> is {} characters long (at most {} expected).", field, translated_file_path.display(), length, max_length);
+        }
+    }
+}
+
+/// Parses a `FIELD=LENGTH` length limit.
+fn parse_length_limit(value: &str) -> std::result::Result<(String, usize), String> {
+    let (field, max_length) = value.split_once('=')
+        .ok_or(format!("Invalid length limit '{}', expected e.g. `description=155`", value))?;
+    let max_length = max_length.trim().parse()
+        .map_err(|_| format!("Invalid length in '{}', expected a number of characters", value))?;
+    Ok((field.trim().to_string(), max_length))
+}
+
+/// Translates `--front-matter-fields` of the source (`--front-matter-only`), keeping the rest of the file as-is.
+fn translate_front_matter_fields(
+    original_content: &str,
+    source: &FileMetadata,
+    source_front_matter: &FrontMatter,
+    translator: &dyn Translator,
+    to_lang: &str,
+    cmd_args: &Args,
+) -> Result<String> {
+    let (fields, texts): (Vec<&String>, Vec<String>) = cmd_args.front_matter_fields.iter()
+        .filter_map(|field| Some((field, source_front_matter.fields.get(field.as_str())?.as_str()?.to_string())))
+        .unzip();
+    if texts.is_empty() {
+        return Ok(original_content.to_string())
+    }
+
+    let translated_texts = translator.translate_list(&texts, &source.language_identifier, to_lang);
+    delay(cmd_args);
+    let translated_texts = translated_texts?;
+    if translated_texts.len() != texts.len() {
+        return Err(Error::InvalidTranslation(
+            source.path.clone(),
+            format!("{} front matter field(s) translated instead of {}", translated_texts.len(), texts.len()),
+        ))
+    }
+
+    front_matter::rewrite(original_content, &source.path, &cmd_args.translation_key_field, source_front_matter.format, |translated_fields| {
+        for (field, text) in fields.into_iter().zip(translated_texts) {
+            translated_fields.insert(serde_yaml::Value::from(field.as_str()), serde_yaml::Value::from(text));
+        }
+    })
+}
+
+/// Translates `--list-fields` of the source front matter item by item, so their structure is kept.
+///
+/// NOTE: Fields which are not lists of strings are left to the content translation.
+fn translate_list_fields(
+    source_path: &Path,
+    source_fields: &Mapping,
+    translator: &dyn Translator,
+    from_lang: &str,
+    to_lang: &str,
+    cmd_args: &Args,
+) -> Result<Vec<(String, Vec<String>)>> {
+    let mut translated_fields = vec![];
+    for field in cmd_args.list_fields.iter() {
+        let Some(value) = source_fields.get(field.as_str()) else { continue };
+        let Ok(items) = serde_yaml::from_value::<Vec<String>>(value.clone()) else { continue };
+        if items.is_empty() {
+            continue
+        }
+
+        let translated_items = translator.translate_list(&items, from_lang, to_lang);
+        delay(cmd_args);
+        let translated_items = translated_items?;
+        if translated_items.len() != items.len() {
+            return Err(Error::InvalidTranslation(
+                source_path.to_path_buf(),
+                format!("`{}` has {} item(s) instead of {}", field, translated_items.len(), items.len()),
+            ))
+        }
+        translated_fields.push((field.clone(), translated_items));
+    }
+
+    Ok(translated_fields)
+}
+
+/// Waits for `--delay` after a translator call.
+fn delay(cmd_args: &Args) {
+    // Dry runs don't call any backend.
+    if let (Some(delay), false) = (cmd_args.delay, cmd_args.dry_run) {
+        thread::sleep(Duration::from_millis(delay));
+    }
+}
+
+/// Lets the user edit a translation in their editor, and returns the edited content.
+fn review(translation: String, translated_file_path: &Path) -> Result<String> {
+    // NOTE: `$VISUAL` and `$EDITOR` can contain arguments (e.g. `code --wait`).
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| if cfg!(windows) { "notepad".to_string() } else { "vi".to_string() });
+    let mut editor_args = editor.split_whitespace();
+    let Some(editor_command) = editor_args.next() else {
+        return Err(Error::EditorFailed(format!("Invalid editor command '{}'", editor)))
+    };
+
+    // Keep the extension so editors can highlight the content.
+    let extension = translated_file_path.extension().and_then(|e| e.to_str()).unwrap_or("md");
+    let review_dir = translated_file_path.parent().unwrap();
+    fs::create_dir_all(review_dir)?;
+    let (review_file_path, mut review_file) = create_temporary_file(review_dir, "rhesus-review", extension)?;
+    let written = review_file.write_all(translation.as_bytes());
+    drop(review_file);
+    if let Err(err) = written {
+        let _ = fs::remove_file(&review_file_path);
+        return Err(err.into())
+    }
+
+    info!("Reviewing <{}>…", translated_file_path.display());
+    let status = Command::new(editor_command)
+        .args(editor_args)
+        .arg(&review_file_path)
+        .status()
+        .map_err(Error::CommandInvocationFailed)?;
+    let reviewed = fs::read_to_string(&review_file_path)
+        .map_err(|err| Error::CouldNotReadFile(review_file_path.clone(), err));
+    fs::remove_file(&review_file_path)?;
+
+    if !status.success() {
+        return Err(Error::EditorFailed(format!("'{}' exited with {}", editor, status)))
+    }
+    reviewed
+}
+
+/// Creates a hidden file with a random name in `dir`, never reusing an existing file
+/// (e.g. one left by an interrupted run, or used by another run).
+fn create_temporary_file(dir: &Path, name: &str, extension: &str) -> Result<(PathBuf, fs::File)> {
+    loop {
+        let path = dir.join(format!(".{}-{:08x}.{}", name, rand::random::<u32>(), extension));
+        match fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+/// Finds content files in `directory`, with entries which couldn't be read (e.g. permission errors or broken symbolic links).
+fn find_content_files(directory: &PathBuf, extensions: &[&str]) -> (Vec<PathBuf>, Vec<Error>) {
+    let mut content_files = Vec::new();
+    let mut errors = Vec::new();
+
+    // Content directories of new languages don't necessarily exist yet.
+    if !directory.exists() {
+        return (content_files, errors)
+    }
+
+    for entry in WalkDir::new(directory).sort_by_file_name() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                errors.push(Error::Walk(err));
+                continue
+            },
+        };
+        // Hugo ignores hidden files (e.g. temporary files of `--review` left by an interrupted run).
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue
+        }
+        let path = entry.into_path();
+        if let Some(extension) = path.extension() {
+            if extensions.iter().any(|e| extension == *e) {
+                content_files.push(path);
+            }
+        }
+    }
+
+    (content_files, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Directory of fixtures used by tests.
+    fn fixture(path: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(path)
+    }
+
+    /// Arguments of a `translate` run in the site at `root`.
+    ///
+    /// NOTE: Planning runs Hugo unless `--drafts`, `--future` and `--expired` are passed.
+    fn args(root: &Path, arguments: &[&str]) -> Args {
+        let cli = Cli::parse_from(["rhesus-macaque", "--no-lock"].iter().chain(arguments));
+        Args { global: GlobalArgs { root: root.to_path_buf(), ..cli.global }, ..cli.translate }
+    }
+
+    /// Site in a temporary directory, removed when dropped.
+    struct TestSite {
+        root: PathBuf,
+    }
+
+    impl TestSite {
+        /// Site with `en` and `fr` languages, in `content/en` and `content/fr`.
+        const CONFIG: &'static str = "
+defaultcontentlanguage: en
+languages:
+  en:
+    languagename: English
+    weight: 1
+  fr:
+    languagename: Français
+    weight: 2
+module:
+  mounts:
+  - source: content/en
+    lang: en
+  - source: content/fr
+    lang: fr
+";
+
+        fn new() -> Self {
+            let root = env::temp_dir().join(format!("rhesus-macaque-test-{:08x}", rand::random::<u32>()));
+            fs::create_dir_all(root.join("content/en")).unwrap();
+            fs::create_dir_all(root.join("content/fr")).unwrap();
+            Self { root: fs::canonicalize(root).unwrap() }
+        }
+
+        fn write(&self, path: &str, content: &str) -> PathBuf {
+            let path = self.root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, content).unwrap();
+            path
+        }
+
+        /// Writes a page with a `Hello` title and body, in the `translation_key` translation group.
+        fn write_page(&self, path: &str, translation_key: &str) -> PathBuf {
+            self.write(path, &format!("---\ntitle: Hello\ntranslationKey: {}\n---\n\nHello.\n", translation_key))
+        }
+
+        fn hugo_config(&self) -> HugoConfig {
+            HugoConfig::new(serde_yaml::from_str(Self::CONFIG).unwrap(), self.root.clone())
+        }
+
+        fn args(&self, arguments: &[&str]) -> Args {
+            args(&self.root, arguments)
+        }
+
+        fn job(&self, path: &str, to_lang: &str, cmd_args: &Args) -> TranslationJob {
+            let metadata = FileMetadata::try_from(self.root.join(path), "en".to_string(), &cmd_args.translation_key_field).unwrap();
+            TranslationJob::new(Box::new(metadata), to_lang.to_string(), &self.hugo_config()).unwrap()
+        }
+
+        /// Translates `job` with a [`TestTranslator`], returning the translation.
+        fn translate(&self, job: &TranslationJob, cmd_args: &Args) -> String {
+            let hugo_config = self.hugo_config();
+            let glossary = Glossary::new(hugo_config.taxonomy_fields.clone());
+            let (path, _) = translate(job, &TestTranslator::default(), &hugo_config, &glossary, None, cmd_args).unwrap();
+            fs::read_to_string(path).unwrap()
+        }
+    }
+
+    impl Drop for TestSite {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    /// Translator which answers `Bonjour` for `Hello`, and records prompts it was sent
+    /// and why its answers would have been rejected if they had been pasted manually.
+    #[derive(Default)]
+    struct TestTranslator {
+        prompts: Mutex<Vec<String>>,
+        paste_mistakes: Mutex<Vec<String>>,
+    }
+
+    impl Translator for TestTranslator {
+        fn generator(&self) -> &str {
+            "TEST"
+        }
+
+        fn translate_path(&self, path: &Path, _from_lang: &str, _to_lang: &str) -> Result<PathBuf> {
+            Ok(path.to_owned())
+        }
+
+        fn translate_content(&self, content: &Content, from_lang: &str, to_lang: &str, source_hash: String, correction: Option<&str>) -> Result<String> {
+            let prompt = self.content_translate_prompt(content, from_lang, to_lang, source_hash, correction);
+            let translation = content.text.replace("Hello", "Bonjour");
+            self.paste_mistakes.lock().unwrap().extend(translator::paste_mistake(&translation, &prompt, content));
+            self.prompts.lock().unwrap().push(prompt);
+            Ok(translation)
+        }
+
+        fn translate_list(&self, items: &[String], _from_lang: &str, _to_lang: &str) -> Result<Vec<String>> {
+            Ok(items.iter().map(|item| item.replace("Hello", "Bonjour")).collect())
+        }
+    }
+
+    #[test]
+    fn html_content_files_are_found_with_html() {
+        let directory = fixture("html/en");
+
+        let (markdown_files, _) = find_content_files(&directory, &["md"]);
+        let (content_files, _) = find_content_files(&directory, &["md", "html"]);
+
+        assert_eq!(markdown_files, vec![directory.join("index.md")]);
+        assert_eq!(content_files, vec![directory.join("about.html"), directory.join("index.md")]);
+    }
+
+    #[test]
+    fn html_content_files_are_translated_as_html() {
+        let path = fixture("html/en/about.html");
+        let metadata = FileMetadata::try_from(path.clone(), "en".to_string(), "translationKey").unwrap();
+        assert_eq!(metadata.translation_keys, HashSet::from(["about".to_string()]));
+
+        let text = read_content_file(&path).unwrap();
+        let content = Content::new(&text, &path);
+        let translator = TestTranslator::default();
+        translator.translate_content(&content, "en", "fr", "hash".to_string(), None).unwrap();
+
+        let prompts = translator.prompts.lock().unwrap();
+        assert!(prompts[0].contains("Hugo SSG HTML content file"), "{}", prompts[0]);
+        assert!(prompts[0].contains("Keep HTML tags"), "{}", prompts[0]);
+        assert!(prompts[0].contains("```html\n---\n"), "{}", prompts[0]);
+    }
+
+    #[test]
+    fn draft_translations_are_drafts_with_include_drafts_as_drafts() {
+        let site = TestSite::new();
+        // Pages can be drafts because of a `cascade`, without a `draft` field.
+        site.write_page("content/en/post.md", "post");
+        site.write_page("content/en/other.md", "other");
+        let cmd_args = site.args(&["--include-drafts-as-drafts"]);
+
+        let mut job = site.job("content/en/post.md", "fr", &cmd_args);
+        job.source.is_draft = true;
+        let translation = site.translate(&job, &cmd_args);
+        let front_matter = FrontMatter::parse(&translation, &job.source.path, "translationKey").unwrap();
+        assert_eq!(front_matter.fields.get("draft"), Some(&serde_yaml::Value::Bool(true)), "{}", translation);
+
+        let translation = site.translate(&site.job("content/en/other.md", "fr", &cmd_args), &cmd_args);
+        let front_matter = FrontMatter::parse(&translation, &job.source.path, "translationKey").unwrap();
+        assert_eq!(front_matter.fields.get("draft"), None, "{}", translation);
+    }
+
+    #[test]
+    fn non_utf8_files_are_reported() {
+        let path = fixture("non-utf8/latin-1.md");
+        let err = FileMetadata::try_from(path.clone(), "en".to_string(), "translationKey").unwrap_err();
+        assert!(matches!(&err, Error::NotUtf8(err_path, 14) if err_path == &path), "{:?}", err);
+
+        // Other files are still translated.
+        let site = TestSite::new();
+        fs::copy(&path, site.root.join("content/en/latin-1.md")).unwrap();
+        site.write_page("content/en/post.md", "post");
+        let cmd_args = site.args(&["--drafts", "--future", "--expired"]);
+        let hugo_config = site.hugo_config();
+        let (jobs, summary) = plan_jobs(&cmd_args, &hugo_config, &Glossary::new(vec![])).unwrap();
+        assert_eq!(summary.scanned_files, 2);
+        assert_eq!(jobs.iter().map(|job| &job.content_file_path).collect::<Vec<_>>(), vec![Path::new("post.md")]);
+    }
+
+    #[test]
+    fn legacy_content_dirs_are_used_without_mounts() {
+        let root = fs::canonicalize(fixture("legacy-content-dir")).unwrap();
+        let config = serde_yaml::from_str(&fs::read_to_string(root.join("config.yaml")).unwrap()).unwrap();
+
+        let hugo_config = HugoConfig::new(config, root.clone());
+
+        let content_dirs: Vec<_> = hugo_config.language_configs.iter()
+            .map(|(lang, config)| (lang.as_str(), config.content_dir.clone()))
+            .collect();
+        assert_eq!(content_dirs, vec![("en", root.join("pages")), ("fr", root.join("pages-fr"))]);
+    }
+
+    #[test]
+    fn absolute_mount_sources_are_canonicalized() {
+        let site = TestSite::new();
+        site.write_page("content/en/post.md", "post");
+        site.write_page("content/en/other.md", "other");
+        let translation = site.write_page("content/fr/post.md", "post");
+        let config = TestSite::CONFIG.replace("source: content/fr", &format!("source: {}", site.root.join("content/en/../fr").display()));
+
+        let hugo_config = HugoConfig::new(serde_yaml::from_str(&config).unwrap(), site.root.clone());
+
+        assert_eq!(hugo_config.language_configs["fr"].content_dir, site.root.join("content/fr"));
+        assert_eq!(hugo_config.content_language(&translation).map(String::as_str), Some("fr"));
+        let cmd_args = site.args(&["--drafts", "--future", "--expired"]);
+        let (jobs, _) = plan_jobs(&cmd_args, &hugo_config, &Glossary::new(vec![])).unwrap();
+        let jobs: Vec<_> = jobs.iter().map(|job| (job.content_file_path.as_path(), job.to_lang.as_str())).collect();
+        assert_eq!(jobs, vec![(Path::new("other.md"), "fr")]);
+    }
+
+    #[test]
+    fn front_matter_only_keeps_the_body_byte_for_byte() {
+        let site = TestSite::new();
+        let source = "---\ntitle: Hello\ntranslationKey: post\n---\n\nHello,  world.  \n\n* Hello\n\n\n";
+        site.write("content/en/post.md", source);
+        let cmd_args = site.args(&["--front-matter-only"]);
+
+        let translation = site.translate(&site.job("content/en/post.md", "fr", &cmd_args), &cmd_args);
+
+        let (front_matter, body) = front_matter::split_raw(&translation).unwrap();
+        assert_eq!(body, front_matter::split_raw(source).unwrap().1);
+        assert!(front_matter.contains("title: Bonjour\n"), "{}", translation);
+    }
+
+    #[test]
+    fn body_only_keeps_the_front_matter_byte_for_byte() {
+        let site = TestSite::new();
+        let source = "---\n# Comment\ntitle:   Hello\ntags: [ a,b ]\ntranslationKey: post\n---\n\nHello.\n";
+        site.write("content/en/post.md", source);
+        let cmd_args = site.args(&["--body-only"]);
+
+        let translation = site.translate(&site.job("content/en/post.md", "fr", &cmd_args), &cmd_args);
+
+        let (front_matter, body) = front_matter::split_raw(&translation).unwrap();
+        // Only the `translator` field is added.
+        assert!(front_matter.contains("translator: TEST\n"), "{}", translation);
+        let front_matter = front_matter.replace("translator: TEST\n", "");
+        assert_eq!(front_matter, front_matter::split_raw(source).unwrap().0);
+        assert_eq!(body, "\nBonjour.\n");
+    }
+
+    #[test]
+    fn body_only_translations_can_be_pasted_without_translation_key() {
+        let site = TestSite::new();
+        site.write_page("content/en/post.md", "post");
+        let cmd_args = site.args(&["--body-only"]);
+        let hugo_config = site.hugo_config();
+        let translator = TestTranslator::default();
+
+        translate(&site.job("content/en/post.md", "fr", &cmd_args), &translator, &hugo_config, &Glossary::new(vec![]), None, &cmd_args).unwrap();
+
+        // The pasted body can't contain the translation key, which is in the front matter.
+        assert_eq!(*translator.paste_mistakes.lock().unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn files_of_overlapping_mounts_belong_to_the_most_specific_one() {
+        let root = fs::canonicalize(fixture("overlapping-mounts")).unwrap();
+        let config = serde_yaml::from_str(&fs::read_to_string(root.join("config.yaml")).unwrap()).unwrap();
+        let hugo_config = HugoConfig::new(config, root.clone());
+        let cmd_args = args(&root, &["--drafts", "--future", "--expired"]);
+
+        let (jobs, summary) = plan_jobs(&cmd_args, &hugo_config, &Glossary::new(vec![])).unwrap();
+
+        let jobs: Vec<_> = jobs.iter()
+            .map(|job| (job.source.path.strip_prefix(&root).unwrap(), job.content_file_path.as_path(), job.to_lang.as_str()))
+            .collect();
+        assert_eq!(jobs, vec![
+            (Path::new("content/fr/only-fr.md"), Path::new("only-fr.md"), "en"),
+            (Path::new("content/other.md"), Path::new("other.md"), "fr"),
+        ]);
+        assert_eq!(summary.fully_translated, 1);
+    }
+
+    #[test]
+    fn global_arguments_are_accepted_before_and_after_subcommands() {
+        for arguments in [["rhesus-macaque", "--root", "site", "list"], ["rhesus-macaque", "list", "--root", "site"]] {
+            let cli = Cli::try_parse_from(arguments).unwrap();
+            assert_eq!(cli.global.root, PathBuf::from("site"), "{:?}", arguments);
+            assert!(matches!(cli.command, Some(Subcommand::List(_))), "{:?}", arguments);
+        }
+    }
+
+    #[test]
+    fn locks_of_dead_processes_are_taken_over() {
+        let site = TestSite::new();
+        // NOTE: Process identifiers are at most 2^22 on Linux.
+        let lock_path = site.write(".rhesus.lock", "4294967295");
+
+        let lock = RunLock::acquire(&site.root).unwrap();
+        assert_eq!(fs::read_to_string(&lock_path).unwrap(), std::process::id().to_string());
+        assert!(matches!(RunLock::acquire(&site.root), Err(Error::Locked(..))));
+        drop(lock);
+        assert!(!lock_path.exists());
+    }
+
+    #[test]
+    fn orphans_are_only_deleted_from_the_output_dir() {
+        let site = TestSite::new();
+        let orphan = "---\ntitle: Bonjour\ntranslationKey: old\ntranslator: TEST\n---\n\nBonjour.\n";
+        let site_orphan = site.write("content/fr/old.md", orphan);
+        let output_orphan = site.write("out/content/fr/old.md", orphan);
+        let output_dir = site.root.join("out");
+        let cmd_args = site.args(&["--drafts", "--future", "--expired", "--sync", "--output-dir", output_dir.to_str().unwrap()]);
+
+        let (_, summary) = plan_jobs(&cmd_args, &site.hugo_config(), &Glossary::new(vec![])).unwrap();
+        assert_eq!(summary.orphans, vec![site_orphan.clone()]);
+        delete_orphans(&summary.orphans, &cmd_args).unwrap();
+
+        assert!(site_orphan.exists());
+        assert!(!output_orphan.exists());
+    }
+
+    #[test]
+    fn reports_list_every_language_of_every_translation_group() {
+        let site = TestSite::new();
+        site.write_page("content/en/a.md", "a");
+        site.write("content/fr/b.md", "---\ntitle: Bonjour\ntranslationKey: b\nnoTranslate: true\n---\n\nBonjour.\n");
+        let cmd_args = site.args(&["--drafts", "--future", "--expired"]);
+        let (jobs, summary) = plan_jobs(&cmd_args, &site.hugo_config(), &Glossary::new(vec![])).unwrap();
+        let report_path = site.root.join("report.md");
+
+        let report = Report {
+            root: &site.root,
+            generator: "TEST",
+            stats: None,
+            languages: vec!["en", "fr"],
+            translation_groups: &summary.translation_groups,
+            jobs: jobs.iter().map(|job| (job, Outcome::Saved(site.root.join("content/fr/a.md")))).collect(),
+            source_hashes: None,
+        };
+        report.save(&report_path).unwrap();
+
+        let report = fs::read_to_string(report_path).unwrap();
+        assert!(report.contains("| a | en | Existing | content/en/a.md |  |\n"), "{}", report);
+        assert!(report.contains("| a | fr | Generated | content/fr/a.md | content/en/a.md (en) |\n"), "{}", report);
+        assert!(report.contains("| b | en | Missing |  |  |\n"), "{}", report);
+        assert!(report.contains("| b | fr | Existing | content/fr/b.md |  |\n"), "{}", report);
+    }
+
+    #[test]
+    fn failures_of_previous_runs_are_kept_until_they_succeed() {
+        let site = TestSite::new();
+        let failure = |source: &str| FailedTranslation { source: PathBuf::from(source), from_lang: "en".to_string(), to_lang: "fr".to_string(), existing_translation: None };
+
+        failures::save(&site.root, &[], &[failure("content/en/a.md"), failure("content/en/b.md")]).unwrap();
+        // E.g. an interrupted `--retry-failed` run, which didn't start the second translation.
+        failures::save(&site.root, &[failure("content/en/a.md")], &[]).unwrap();
+        assert_eq!(failures::load(&site.root).unwrap(), vec![failure("content/en/b.md")]);
+
+        failures::save(&site.root, &[failure("content/en/b.md")], &[]).unwrap();
+        assert_eq!(failures::load(&site.root).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn max_files_limits_source_files() {
+        let site = TestSite::new();
+        for key in ["a", "b"] {
+            site.write_page(&format!("content/en/{}.md", key), key);
+        }
+        let cmd_args = site.args(&[]);
+        let mut outdated = site.job("content/en/a.md", "fr", &cmd_args);
+        outdated.existing_translation = Some(site.root.join("content/fr/a.md"));
+        let mut jobs = vec![site.job("content/en/a.md", "fr", &cmd_args), outdated, site.job("content/en/b.md", "fr", &cmd_args)];
+
+        limit_jobs(&mut jobs, 1);
+
+        assert_eq!(jobs.len(), 2);
+        assert!(jobs.iter().all(|job| job.content_file_path == Path::new("a.md")));
+    }
+
+    #[test]
+    fn runs_continue_where_interrupted_runs_stopped() {
+        let site = TestSite::new();
+        for key in ["a", "b", "c"] {
+            site.write_page(&format!("content/en/{}.md", key), key);
+        }
+        let cmd_args = site.args(&["--drafts", "--future", "--expired", "--since-hash-file"]);
+        let hugo_config = site.hugo_config();
+        let glossary = Glossary::new(vec![]);
+        let hashes_path = cmd_args.since_hash_file.clone().unwrap();
+        let plan = || {
+            let (jobs, summary) = plan_jobs(&cmd_args, &hugo_config, &glossary).unwrap();
+            let paths: Vec<_> = jobs.iter().map(|job| job.content_file_path.clone()).collect();
+            (jobs, summary, paths)
+        };
+
+        // Interrupted after the first translation, while writing the second one.
+        let (jobs, summary, paths) = plan();
+        assert_eq!(paths, vec![PathBuf::from("a.md"), PathBuf::from("b.md"), PathBuf::from("c.md")]);
+        let (translated_file_path, source) = translate(&jobs[0], &TestTranslator::default(), &hugo_config, &glossary, None, &cmd_args).unwrap();
+        save_source_hashes(&cmd_args, &hashes_path, &summary, &[(jobs[0].clone(), translated_file_path, source)]).unwrap();
+        let (temporary_path, _) = create_temporary_file(&site.root.join("content/fr"), "b.md", "rhesus-tmp").unwrap();
+
+        let (jobs, summary, paths) = plan();
+        assert_eq!(paths, vec![PathBuf::from("b.md"), PathBuf::from("c.md")]);
+        // Sources and the first translation.
+        assert_eq!(summary.scanned_files, 4, "{} is not a content file", temporary_path.display());
+        let saved: Vec<_> = jobs.iter().map(|job| {
+            let (translated_file_path, source) = translate(job, &TestTranslator::default(), &hugo_config, &glossary, None, &cmd_args).unwrap();
+            (job.clone(), translated_file_path, source)
+        }).collect();
+        save_source_hashes(&cmd_args, &hashes_path, &summary, &saved).unwrap();
+
+        let (_, summary, paths) = plan();
+        assert!(paths.is_empty(), "{:?}", paths);
+        assert_eq!(summary.fully_translated, 3);
+    }
 }
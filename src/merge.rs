@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+use crate::hashes::{self, SourceHashes};
+
+/// File, next to the `--since-hash-file`, in which sources are saved for `--merge`.
+pub const SOURCES_FILE_NAME: &str = ".rhesus-sources.json";
+
+/// Sources translations were made from, by translation key then language, so `--merge` can find
+/// which paragraphs changed since.
+#[derive(Debug, Default)]
+pub struct SourceSnapshots {
+    path: PathBuf,
+    sources: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl SourceSnapshots {
+    /// Loads sources saved next to `hashes_path` (relative to the site root), if the file exists.
+    pub fn load(root: &Path, hashes_path: &Path) -> Result<Self> {
+        let path = root.join(hashes_path).with_file_name(SOURCES_FILE_NAME);
+        if !path.exists() {
+            return Ok(Self { path, sources: BTreeMap::new() })
+        }
+
+        let json = fs::read_to_string(&path)
+            .map_err(|err| Error::CouldNotReadFile(path.clone(), err))?;
+        Ok(Self { sources: serde_json::from_str(&json)?, path })
+    }
+
+    /// Forgets sources which are not the ones recorded in `source_hashes`
+    /// (e.g. translated again without `--merge`), as translations don't match them anymore.
+    pub fn retain_recorded(&mut self, source_hashes: &SourceHashes) {
+        for (translation_key, sources) in self.sources.iter_mut() {
+            sources.retain(|lang, source| source_hashes.get(translation_key, lang) == Some(&hashes::source_hash(source)));
+        }
+    }
+
+    /// Source the `lang` translation of `translation_key` was made from, if known.
+    pub fn get(&self, translation_key: &str, lang: &str) -> Option<&String> {
+        self.sources.get(translation_key)?.get(lang)
+    }
+
+    pub fn set(&mut self, translation_key: &str, lang: &str, source: String) {
+        self.sources.entry(translation_key.to_string()).or_default().insert(lang.to_string(), source);
+    }
+
+    pub fn save(&self) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(&self.sources)?)?;
+        Ok(())
+    }
+}
+
+/// Splits a body into paragraphs (blocks separated by blank lines), keeping fenced code blocks whole.
+fn paragraphs(body: &str) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut lines: Vec<&str> = Vec::new();
+    let mut in_code_block = false;
+    for line in body.lines() {
+        if line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~") {
+            in_code_block = !in_code_block;
+        }
+        if line.trim().is_empty() && !in_code_block {
+            if !lines.is_empty() {
+                paragraphs.push(lines.join("\n"));
+                lines.clear();
+            }
+        } else {
+            lines.push(line);
+        }
+    }
+    if !lines.is_empty() {
+        paragraphs.push(lines.join("\n"));
+    }
+    paragraphs
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Change {
+    /// Paragraph at this index in the previous source is still there.
+    Kept(usize),
+    Removed,
+    /// Paragraph at this index in the current source is new (or was edited).
+    Added(usize),
+}
+
+/// Changes between two lists of paragraphs, keeping as many paragraphs as possible
+/// (longest common subsequence).
+fn diff(previous: &[String], current: &[String]) -> Vec<Change> {
+    // `lengths[i][j]` is the number of paragraphs kept between `previous[i..]` and `current[j..]`.
+    let mut lengths = vec![vec![0usize; current.len() + 1]; previous.len() + 1];
+    for i in (0..previous.len()).rev() {
+        for j in (0..current.len()).rev() {
+            lengths[i][j] = if previous[i] == current[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < previous.len() || j < current.len() {
+        if i < previous.len() && j < current.len() && previous[i] == current[j] {
+            changes.push(Change::Kept(i));
+            (i, j) = (i + 1, j + 1);
+        } else if j < current.len() && (i == previous.len() || lengths[i][j + 1] >= lengths[i + 1][j]) {
+            changes.push(Change::Added(j));
+            j += 1;
+        } else {
+            changes.push(Change::Removed);
+            i += 1;
+        }
+    }
+    changes
+}
+
+/// Updates the body of a translation made from `previous_source` so it matches `source`:
+/// removed paragraphs are removed, consecutive added (or edited) paragraphs are translated together
+/// with `translate`, and other paragraphs are kept as they are in the translation.
+///
+/// Returns `None` if paragraphs of the translation don't match the ones of `previous_source`
+/// (e.g. a paragraph was split while translating), in which case changes can't be located.
+///
+/// NOTE: Paragraphs of the result are separated by one blank line.
+pub fn merge_paragraphs(
+    previous_source: &str,
+    source: &str,
+    translation: &str,
+    mut translate: impl FnMut(&str) -> Result<String>,
+) -> Result<Option<String>> {
+    let previous = paragraphs(previous_source);
+    let current = paragraphs(source);
+    let translated = paragraphs(translation);
+    if previous.len() != translated.len() {
+        return Ok(None)
+    }
+
+    let mut merged = Vec::new();
+    let mut added: Vec<&str> = Vec::new();
+    for change in diff(&previous, &current) {
+        match change {
+            Change::Kept(i) => {
+                if !added.is_empty() {
+                    merged.push(translate(&added.join("\n\n"))?.trim().to_string());
+                    added.clear();
+                }
+                merged.push(translated[i].clone());
+            },
+            Change::Removed => {},
+            Change::Added(j) => added.push(&current[j]),
+        }
+    }
+    if !added.is_empty() {
+        merged.push(translate(&added.join("\n\n"))?.trim().to_string());
+    }
+
+    // Keep the blank line translations often have after their front matter.
+    let leading = if translation.starts_with(['\n', '\r']) { "\n" } else { "" };
+    Ok(Some(format!("{}{}\n", leading, merged.join("\n\n"))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Translates to uppercase, and records what was sent.
+    fn translate<'a>(sent: &'a mut Vec<String>) -> impl FnMut(&str) -> Result<String> + 'a {
+        move |text| {
+            sent.push(text.to_string());
+            Ok(text.to_uppercase())
+        }
+    }
+
+    #[test]
+    fn only_changed_paragraphs_are_translated() {
+        let previous = "\nOne.\n\nTwo.\n\nThree.\n";
+        let source = "\nOne.\n\nTwo, edited.\n\nThree.\n\nFour.\n";
+        let translation = "\nUn (tweaked).\n\nDeux.\n\nTrois.\n";
+        let mut sent = vec![];
+
+        let merged = merge_paragraphs(previous, source, translation, translate(&mut sent)).unwrap();
+
+        assert_eq!(merged.as_deref(), Some("\nUn (tweaked).\n\nTWO, EDITED.\n\nTrois.\n\nFOUR.\n"));
+        assert_eq!(sent, vec!["Two, edited.", "Four."]);
+    }
+
+    #[test]
+    fn removed_paragraphs_are_removed() {
+        let mut sent = vec![];
+        let merged = merge_paragraphs("One.\n\nTwo.\n", "Two.\n", "Un.\n\nDeux.\n", translate(&mut sent)).unwrap();
+
+        assert_eq!(merged.as_deref(), Some("Deux.\n"));
+        assert!(sent.is_empty());
+    }
+
+    #[test]
+    fn consecutive_changes_are_translated_together() {
+        let mut sent = vec![];
+        merge_paragraphs("One.\n\nFour.\n", "One.\n\nTwo.\n\nThree.\n\nFour.\n", "Un.\n\nQuatre.\n", translate(&mut sent)).unwrap();
+
+        assert_eq!(sent, vec!["Two.\n\nThree."]);
+    }
+
+    #[test]
+    fn mismatched_paragraphs_are_not_merged() {
+        let mut sent = vec![];
+        let merged = merge_paragraphs("One.\n\nTwo.\n", "One.\n\nTwo!\n", "Un. Deux.\n", translate(&mut sent)).unwrap();
+
+        assert_eq!(merged, None);
+        assert!(sent.is_empty());
+    }
+
+    #[test]
+    fn code_blocks_are_one_paragraph() {
+        assert_eq!(paragraphs("Text.\n\n```sh\necho 1\n\necho 2\n```\n"), vec!["Text.", "```sh\necho 1\n\necho 2\n```"]);
+    }
+}
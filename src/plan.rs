@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::failures::FailedTranslation;
+
+/// Version of the plan format, increased when it changes in a backward-incompatible way.
+const PLAN_VERSION: u32 = 1;
+
+/// Translation jobs written by `--plan-out` and executed by `--plan-in`, so they can be reviewed
+/// (and trimmed) before translating.
+///
+/// Format (JSON):
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "translations": [
+///     { "source": "content/en/blog/post.md", "from_lang": "en", "to_lang": "fr" }
+///   ]
+/// }
+/// ```
+///
+/// Paths are relative to the site root. Translations which replace an outdated one
+/// also have an `existing_translation` path. Entries use the format of the failures file.
+///
+/// NOTE: Target paths are not part of the plan as they are translated when running it,
+///   and source hashes are only recorded with `--since-hash-file`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Plan {
+    version: u32,
+    translations: Vec<FailedTranslation>,
+}
+
+pub fn load(path: &Path) -> Result<Vec<FailedTranslation>> {
+    let json = fs::read_to_string(path)
+        .map_err(|err| Error::CouldNotReadFile(path.to_path_buf(), err))?;
+    let plan: Plan = serde_json::from_str(&json)?;
+    if plan.version != PLAN_VERSION {
+        return Err(Error::UnsupportedPlanVersion(plan.version, PLAN_VERSION))
+    }
+    Ok(plan.translations)
+}
+
+pub fn save(path: &Path, translations: Vec<FailedTranslation>) -> Result<()> {
+    let plan = Plan { version: PLAN_VERSION, translations };
+    fs::write(path, serde_json::to_string_pretty(&plan)?)?;
+    Ok(())
+}
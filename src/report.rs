@@ -0,0 +1,153 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::TranslationJob;
+use crate::error::Result;
+use crate::hashes::SourceHashes;
+use crate::stats::Stats;
+
+/// What happened to a translation during the run.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    /// Existed before the run, and was left as-is.
+    Existing(PathBuf),
+    Saved(PathBuf),
+    /// Would have been saved there, in dry runs.
+    Planned(PathBuf),
+    Failed(String),
+    /// Not started because of `--timeout` or Ctrl-C.
+    NotStarted,
+    /// Doesn't exist and wasn't planned (e.g. `noTranslate`, `--max-files` or `--bootstrap`).
+    Missing,
+}
+
+/// Human-readable report of a run (`--report`), to share with people who don't read logs.
+///
+/// Every language of every translation group is listed, whether it was translated during the run or not.
+///
+/// NOTE: Source hashes are only recorded with `--since-hash-file`, so they are only listed then.
+#[derive(Debug)]
+pub struct Report<'a> {
+    pub root: &'a Path,
+    pub generator: &'a str,
+    pub stats: Option<Stats>,
+    pub languages: Vec<&'a str>,
+    /// Existing files of each translation group, by translation key then language.
+    pub translation_groups: &'a BTreeMap<String, BTreeMap<String, PathBuf>>,
+    /// Jobs of the run.
+    pub jobs: Vec<(&'a TranslationJob, Outcome)>,
+    pub source_hashes: Option<SourceHashes>,
+}
+
+/// Translation of a translation group in a language, and the job of the run which translated it (if any).
+type Row<'a> = (&'a str, &'a str, Option<&'a TranslationJob>, Outcome);
+
+impl<'a> Report<'a> {
+    /// Writes the report as HTML if `path` ends with `.html`, as Markdown otherwise.
+    pub fn save(self, path: &Path) -> Result<()> {
+        let report = match path.extension() {
+            Some(extension) if extension == "html" => self.to_html(),
+            _ => self.to_markdown(),
+        };
+        fs::write(path, report)?;
+
+        Ok(())
+    }
+
+    /// Rows grouped by translation key, then sorted by language.
+    fn rows(&self) -> Vec<Row<'_>> {
+        let mut rows: BTreeMap<(&str, &str), (Option<&TranslationJob>, Outcome)> = BTreeMap::new();
+        for (translation_key, translations) in self.translation_groups.iter() {
+            for lang in self.languages.iter() {
+                let outcome = match translations.get(*lang) {
+                    Some(path) => Outcome::Existing(path.clone()),
+                    None => Outcome::Missing,
+                };
+                rows.insert((translation_key, lang), (None, outcome));
+            }
+        }
+        // Jobs can replace existing translations (e.g. outdated ones).
+        for (job, outcome) in self.jobs.iter() {
+            rows.insert((translation_key(job), &job.to_lang), (Some(*job), outcome.clone()));
+        }
+
+        rows.into_iter().map(|((translation_key, lang), (job, outcome))| (translation_key, lang, job, outcome)).collect()
+    }
+
+    fn headers(&self) -> Vec<&'static str> {
+        let mut headers = vec!["Translation key", "Language", "Status", "Details", "Source"];
+        if self.source_hashes.is_some() {
+            headers.push("Source hash");
+        }
+        headers
+    }
+
+    fn cells(&self, (translation_key, lang, job, outcome): &Row) -> Vec<String> {
+        let relative = |path: &Path| path.strip_prefix(self.root).unwrap_or(path).display().to_string();
+        let (status, details) = match outcome {
+            Outcome::Existing(path) => ("Existing", relative(path)),
+            Outcome::Saved(path) => ("Generated", relative(path)),
+            Outcome::Planned(path) => ("Missing (dry run)", relative(path)),
+            Outcome::Failed(err) => ("Failed", err.clone()),
+            Outcome::NotStarted => ("Missing (not started)", String::new()),
+            Outcome::Missing => ("Missing", String::new()),
+        };
+        let source = job
+            .map(|job| format!("{} ({})", relative(&job.source.path), job.source.language_identifier))
+            .unwrap_or_default();
+        let mut cells = vec![translation_key.to_string(), lang.to_string(), status.to_string(), details, source];
+        if let Some(source_hashes) = &self.source_hashes {
+            cells.push(source_hashes.get(translation_key, lang).cloned().unwrap_or_default());
+        }
+        cells
+    }
+
+    fn summary(&self, rows: &[Row]) -> String {
+        let translation_keys: HashSet<_> = rows.iter().map(|(translation_key, ..)| translation_key).collect();
+        let mut summary = format!("{} translation group(s), {} translation(s) by {}", translation_keys.len(), self.jobs.len(), self.generator);
+        if let Some(stats) = self.stats {
+            summary.push_str(&format!(", {} API call(s) ({}ms on average), {} retry(ies)", stats.api_calls, stats.average_latency_ms(), stats.retries));
+        }
+        summary.push('.');
+        summary
+    }
+
+    fn to_markdown(&self) -> String {
+        let rows = self.rows();
+        let headers = self.headers();
+        let mut markdown = format!("# Translation report\n\n{}\n\n", self.summary(&rows));
+        markdown.push_str(&format!("| {} |\n", headers.join(" | ")));
+        markdown.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+        for row in rows.iter() {
+            let cells: Vec<_> = self.cells(row).iter().map(|cell| cell.replace('|', "\\|").replace('\n', " ")).collect();
+            markdown.push_str(&format!("| {} |\n", cells.join(" | ")));
+        }
+        markdown
+    }
+
+    fn to_html(&self) -> String {
+        let rows = self.rows();
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Translation report</title></head>\n<body>\n<h1>Translation report</h1>\n<p>{}</p>\n<table>\n",
+            escape_html(&self.summary(&rows)),
+        );
+        let headers: Vec<_> = self.headers().iter().map(|header| format!("<th>{}</th>", header)).collect();
+        html.push_str(&format!("<tr>{}</tr>\n", headers.concat()));
+        for row in rows.iter() {
+            let cells: Vec<_> = self.cells(row).iter().map(|cell| format!("<td>{}</td>", escape_html(cell))).collect();
+            html.push_str(&format!("<tr>{}</tr>\n", cells.concat()));
+        }
+        html.push_str("</table>\n</body>\n</html>\n");
+        html
+    }
+}
+
+/// First translation key (alphabetically) of the job's source.
+fn translation_key(job: &TranslationJob) -> &str {
+    job.source.translation_keys.iter().min().map(String::as_str).unwrap_or_default()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -0,0 +1,43 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Usage statistics of a translator backend, to help tuning `--jobs`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Stats {
+    pub api_calls: usize,
+    /// Translations sent again after an invalid result.
+    pub retries: usize,
+    pub total_latency_ms: u128,
+}
+
+impl Stats {
+    pub fn average_latency_ms(&self) -> u128 {
+        match self.api_calls {
+            0 => 0,
+            calls => self.total_latency_ms / calls as u128,
+        }
+    }
+}
+
+/// Thread-safe [`Stats`] accumulator.
+#[derive(Debug, Default)]
+pub struct StatsRecorder(Mutex<Stats>);
+
+impl StatsRecorder {
+    /// Records an API call which started at `start`.
+    pub fn record_call(&self, start: Instant) {
+        let latency: Duration = start.elapsed();
+        let mut stats = self.0.lock().unwrap();
+        stats.api_calls += 1;
+        stats.total_latency_ms += latency.as_millis();
+    }
+
+    pub fn record_retry(&self) {
+        self.0.lock().unwrap().retries += 1;
+    }
+
+    pub fn snapshot(&self) -> Stats {
+        *self.0.lock().unwrap()
+    }
+}
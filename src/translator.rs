@@ -2,74 +2,351 @@ use clipboard::{ClipboardContext, ClipboardProvider};
 use openai_api_rs::v1::chat_completion::{ChatCompletionRequest, ChatCompletionMessage, MessageRole};
 use openai_api_rs::v1::common::GPT3_5_TURBO_1106;
 use openai_api_rs::v1::api::Client;
-use std::fmt::Display;
-use std::path::PathBuf;
-use std::sync::Mutex;
-use std::{env, io, fmt};
-use std::error::Error;
+use openai_api_rs::v1::error::APIError;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+use std::{env, fs, io};
+use std::io::BufRead;
 
 use crate::Args;
+use crate::error::{Error, Result};
+use crate::stats::{Stats, StatsRecorder};
 
-pub fn auto_detect(args: &Args) -> Result<Box<dyn Translator>, Box<dyn Error>> {
+pub fn auto_detect(args: &Args) -> Result<Box<dyn Translator>> {
     if args.dry_run {
         return Ok(Box::new(DryRunTranslator));
     }
+    let prompts = Prompts::from_args(args)?;
+    if args.prompt_only {
+        return Ok(Box::new(PromptOnlyTranslator::new(prompts)?))
+    }
     if args.auto {
-        return Ok(GPTAutoTranslator::new().map(Box::new)?)
+        return Ok(GPTAutoTranslator::new(prompts, args).map(Box::new)?)
+    }
+    Ok(GPTManualTranslator::new(prompts).map(Box::new)?)
+}
+
+/// Default prompt template for regular pages.
+const PAGE_PROMPT_TEMPLATE: &str = "Translate the following Hugo SSG {format} content file from {from_lang} to {to_lang}.{format_instructions} Do not translate YAML items in `read_allowed` and `aliases`, and the `{translation_key_field}` value. Add `# GENERATED BY {generator}` and `translator: \"{generator}\"` at the beginning of the front matter (only the field if it is JSON, which has no comments). Remove italics from words in {to_lang} and add italics to words in {from_lang}. Do not translate words \"TODO\" and \"FIXME\".\n\n```{code_block_language}\n{text}\n```";
+
+/// Prompt template used to translate only the body of a file (`--body-only`).
+const BODY_PROMPT_TEMPLATE: &str = "Translate the following Hugo SSG {format} text from {from_lang} to {to_lang}. It is the body of a page, without its front matter.{format_instructions} Remove italics from words in {to_lang} and add italics to words in {from_lang}. Do not translate words \"TODO\" and \"FIXME\". Answer with the translated text only, without wrapping it in a code block. Here is the text:\n\n{text}";
+
+/// Default prompt template for section pages (`_index` files).
+const SECTION_PROMPT_TEMPLATE: &str = "Translate the following Hugo SSG {format} section page from {from_lang} to {to_lang}. It introduces a list of pages, only translate its texts (e.g. `title`, `description` and the short body) and do not add any content.{format_instructions} Do not translate YAML keys, items in `read_allowed` and `aliases`, listing configuration (e.g. `cascade`, `outputs`, `menu` identifiers) and the `{translation_key_field}` value. Add `# GENERATED BY {generator}` and `translator: \"{generator}\"` at the beginning of the front matter (only the field if it is JSON, which has no comments). Do not translate words \"TODO\" and \"FIXME\".\n\n```{code_block_language}\n{text}\n```";
+
+static DEFAULT_PROMPTS: Prompts = Prompts {
+    page_template: None,
+    section_template: None,
+    pair_templates: BTreeMap::new(),
+    translation_key_field: Cow::Borrowed("translationKey"),
+    context: None,
+};
+
+/// Maximum size of `--context-file`, so it doesn't take most of the model's context window.
+const MAX_CONTEXT_BYTES: usize = 16 * 1024;
+
+/// Prompt templates, which can be overridden from the command line.
+///
+/// Templates can use `{from_lang}`, `{to_lang}`, `{generator}`, `{format}`, `{format_instructions}`,
+/// `{code_block_language}`, `{translation_key_field}` and `{text}` placeholders.
+#[derive(Debug, Clone)]
+pub struct Prompts {
+    page_template: Option<String>,
+    section_template: Option<String>,
+    /// Templates specific to a language pair, by file stem (`{from}-{to}` or `{from}-{to}.section`).
+    pair_templates: BTreeMap<String, String>,
+    translation_key_field: Cow<'static, str>,
+    /// Background instructions (e.g. a style guide) sent with every translation.
+    context: Option<String>,
+}
+
+fn read_template(path: &PathBuf) -> Result<String> {
+    fs::read_to_string(path).map_err(|err| Error::CouldNotReadFile(path.clone(), err))
+}
+
+impl Prompts {
+    fn from_args(args: &Args) -> Result<Self> {
+        let page_template = args.prompt_template.as_ref().map(read_template).transpose()?;
+        let section_template = args.section_prompt_template.as_ref().map(read_template).transpose()?;
+
+        let mut pair_templates = BTreeMap::new();
+        if let Some(dir) = &args.pair_prompt_templates {
+            let entries = fs::read_dir(dir).map_err(|err| Error::CouldNotReadFile(dir.clone(), err))?;
+            for entry in entries {
+                let path = entry?.path();
+                if path.extension().is_some_and(|extension| extension == "txt") {
+                    if let Some(stem) = path.file_stem() {
+                        pair_templates.insert(stem.to_string_lossy().to_string(), read_template(&path)?);
+                    }
+                }
+            }
+        }
+
+        let context = args.context_file.as_ref().map(read_template).transpose()?;
+        if let (Some(context), Some(path)) = (&context, &args.context_file) {
+            if context.len() > MAX_CONTEXT_BYTES {
+                return Err(Error::ContextTooLong(path.clone(), context.len(), MAX_CONTEXT_BYTES))
+            }
+        }
+
+        Ok(Self {
+            page_template,
+            section_template,
+            pair_templates,
+            translation_key_field: Cow::Owned(args.translation_key_field.clone()),
+            context,
+        })
+    }
+
+    /// Instructions introducing `--context-file`, if any.
+    fn context_instructions(&self) -> Option<String> {
+        self.context.as_ref().map(|context| format!("Use this style guide and terminology:\n\n{}", context.trim()))
+    }
+
+    /// Template for a content file, preferring the language pair's one if it exists.
+    fn content_template(&self, content: &Content, from_lang: &str, to_lang: &str) -> &str {
+        let pair = format!("{}-{}", from_lang, to_lang);
+        if content.body_only {
+            BODY_PROMPT_TEMPLATE
+        } else if content.is_section {
+            self.pair_templates.get(&format!("{}.section", pair))
+                .or(self.section_template.as_ref())
+                .map(String::as_str)
+                .unwrap_or(SECTION_PROMPT_TEMPLATE)
+        } else {
+            self.pair_templates.get(&pair)
+                .or(self.page_template.as_ref())
+                .map(String::as_str)
+                .unwrap_or(PAGE_PROMPT_TEMPLATE)
+        }
     }
-    return Ok(GPTManualTranslator::new().map(Box::new)?)
 }
 
-pub trait Translator {
+/// Whether a content file is a section page (`_index.md`).
+///
+/// NOTE: `index.md` files are [leaf bundles](https://gohugo.io/content-management/page-bundles/#leaf-bundles),
+///   which are regular pages.
+pub fn is_section_page(path: &Path) -> bool {
+    path.file_stem().is_some_and(|stem| stem == "_index")
+}
+
+/// Format of a content file, used to adapt the translation prompt.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContentFormat {
+    Markdown,
+    Html,
+}
+
+impl ContentFormat {
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(extension) if extension == "html" => Self::Html,
+            _ => Self::Markdown,
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Self::Markdown => "markdown",
+            Self::Html => "HTML",
+        }
+    }
+
+    fn code_block_language(&self) -> &str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+        }
+    }
+}
+
+/// Content file to translate.
+#[derive(Debug, Clone, Copy)]
+pub struct Content<'a> {
+    pub text: &'a str,
+    pub format: ContentFormat,
+    pub is_section: bool,
+    /// Known translations of taxonomy terms, as prompt instructions.
+    pub glossary: Option<&'a str>,
+    /// Length limits of front matter fields (e.g. SEO descriptions), as prompt instructions.
+    pub length_limits: Option<&'a str>,
+    /// Conversation this translation belongs to, for translators which keep context between requests.
+    pub conversation: Option<&'a str>,
+    /// Translation key of the file, which translations must keep.
+    pub translation_key: Option<&'a str>,
+    /// Whether `text` is only the body of the file (see `--body-only`).
+    pub body_only: bool,
+    /// Whether the target language is written right-to-left (`languageDirection: rtl`).
+    pub right_to_left: bool,
+    /// Human-readable names of the source and target languages (e.g. "Français"), used in prompts
+    /// instead of language identifiers when known.
+    pub from_lang_name: Option<&'a str>,
+    pub to_lang_name: Option<&'a str>,
+}
+
+impl<'a> Content<'a> {
+    pub fn new(text: &'a str, path: &Path) -> Self {
+        Self {
+            text,
+            format: ContentFormat::from_path(path),
+            is_section: is_section_page(path),
+            glossary: None,
+            length_limits: None,
+            conversation: None,
+            translation_key: None,
+            body_only: false,
+            right_to_left: false,
+            from_lang_name: None,
+            to_lang_name: None,
+        }
+    }
+}
+
+pub trait Translator: Sync {
     /// Name of the generator (eg. "gpt-3.5-turbo-1106", "GPT-4", "DeepL"…).
     fn generator(&self) -> &str;
 
+    /// Prompt templates, translators which don't use prompts can keep the default ones.
+    fn prompts(&self) -> &Prompts {
+        &DEFAULT_PROMPTS
+    }
+
+    /// Maximum number of translations this translator can run concurrently.
+    ///
+    /// Defaults to `1` so translators are only run in parallel when they explicitly support it.
+    fn max_concurrency(&self) -> usize {
+        1
+    }
+
+    /// Whether the translator sends `--context-file` on its own (e.g. as a system message),
+    /// instead of in each prompt.
+    fn sends_context_separately(&self) -> bool {
+        false
+    }
+
+    /// Statistics about API calls made so far, if the translator makes any.
+    fn stats(&self) -> Option<Stats> {
+        None
+    }
+
     /// Translate a file path synchronously.
     fn translate_path(
         &self,
-        path: &PathBuf,
-        from_lang: &String,
-        to_lang: &String,
-    ) -> Result<PathBuf, Box<dyn Error>>;
+        path: &Path,
+        from_lang: &str,
+        to_lang: &str,
+    ) -> Result<PathBuf>;
 
     /// Translate a text synchronously.
+    ///
+    /// `correction` is appended to the prompt when retrying after an invalid translation.
     fn translate_content(
         &self,
-        text: &String,
-        from_lang: &String,
-        to_lang: &String,
+        content: &Content,
+        from_lang: &str,
+        to_lang: &str,
         source_hash: String,
-    ) -> Result<String, Box<dyn Error>>;
+        correction: Option<&str>,
+    ) -> Result<String>;
+
+    /// Translate each item of a list (e.g. `keywords`) synchronously, keeping their order.
+    fn translate_list(
+        &self,
+        items: &[String],
+        from_lang: &str,
+        to_lang: &str,
+    ) -> Result<Vec<String>>;
 
     fn path_translate_prompt(
         &self,
-        path: &PathBuf,
-        from_lang: &String,
-        to_lang: &String,
+        path: &Path,
+        from_lang: &str,
+        to_lang: &str,
     ) -> String {
         format!(r#"Translate the file path "{}" from {} to {}. I know you can't access the file, I want you to translate its path only."#, path.display(), from_lang, to_lang)
     }
 
+    fn list_translate_prompt(
+        &self,
+        items: &[String],
+        from_lang: &str,
+        to_lang: &str,
+    ) -> String {
+        format!(
+            "Translate each item of the following JSON array from {} to {}. Answer with a JSON array of strings containing the translations in the same order, and nothing else.\n\n{}",
+            from_lang, to_lang, serde_json::to_string(items).expect("Strings can be serialized"),
+        )
+    }
+
     /// Prompt sendable to a LLM for content translation.
     fn content_translate_prompt(
         &self,
-        text: &String,
-        from_lang: &String,
-        to_lang: &String,
+        content: &Content,
+        from_lang: &str,
+        to_lang: &str,
+        source_hash: String,
+        correction: Option<&str>,
+    ) -> String {
+        self.content_translate_prompt_for(self.generator(), content, from_lang, to_lang, source_hash, correction)
+    }
+
+    /// [`Translator::content_translate_prompt`] for a given generator (e.g. a fallback model).
+    fn content_translate_prompt_for(
+        &self,
+        generator: &str,
+        content: &Content,
+        from_lang: &str,
+        to_lang: &str,
         _source_hash: String,
+        correction: Option<&str>,
     ) -> String {
+        // NOTE: Identifiers are still used to find templates (e.g. `en-fr.txt`).
+        let template = self.prompts().content_template(content, from_lang, to_lang);
+        let from_lang = content.from_lang_name.unwrap_or(from_lang);
+        let to_lang = content.to_lang_name.unwrap_or(to_lang);
+        let format_instructions = match content.format {
+            ContentFormat::Markdown => "",
+            ContentFormat::Html => " Keep HTML tags and attributes unchanged, translate only the text.",
+        };
         // and `sourceHash: \"{}\"`
-        format!(
-            "Translate the following Hugo SSG markdown content file from {} to {}. Do not translate YAML items in `read_allowed` and the `translationKey` value. Add `# GENERATED BY {}` and `translator: \"{}\"` at the beginning of the YAML front matter (delimited by `---`). Remove italics from words in {} and add italics to words in {}. Do not translate words \"TODO\" and \"FIXME\".\n\n```md\n{}\n```",
-            from_lang,
-            to_lang,
-            self.generator(),
-            self.generator(),
-            // source_hash,
-            to_lang,
-            from_lang,
-            text,
-        )
+        let prompt = template
+            .replace("{from_lang}", from_lang)
+            .replace("{to_lang}", to_lang)
+            .replace("{generator}", generator)
+            .replace("{format}", content.format.name())
+            .replace("{format_instructions}", format_instructions)
+            .replace("{code_block_language}", content.format.code_block_language())
+            .replace("{translation_key_field}", &self.prompts().translation_key_field)
+            // Replace the text last, so placeholder-like strings it contains are kept as-is.
+            .replace("{text}", content.text);
+        let prompt = match content.glossary {
+            Some(glossary) => format!("{}\n\n{}", prompt, glossary),
+            None => prompt,
+        };
+        let prompt = if content.right_to_left {
+            format!("{}\n\n{} is written right-to-left: translate texts only, and keep Markdown syntax, HTML tags, shortcodes and front matter keys unchanged (left-to-right).", prompt, to_lang)
+        } else {
+            prompt
+        };
+        let prompt = match content.length_limits {
+            Some(length_limits) => format!("{}\n\n{}", prompt, length_limits),
+            None => prompt,
+        };
+        let prompt = match self.prompts().context_instructions().filter(|_| !self.sends_context_separately()) {
+            Some(context) => format!("{}\n\n{}", context, prompt),
+            None => prompt,
+        };
+
+        match correction {
+            Some(correction) => format!("{}\n\n{}", prompt, correction),
+            None => prompt,
+        }
     }
 }
 
@@ -80,49 +357,208 @@ impl Translator for DryRunTranslator {
         "DRY_RUN"
     }
 
+    fn max_concurrency(&self) -> usize {
+        usize::MAX
+    }
+
     fn translate_path(
         &self,
-        _path: &PathBuf,
-        _from_lang: &String,
-        _to_lang: &String,
-    ) -> Result<PathBuf, Box<dyn Error>> {
-        Ok("/dev/null".into())
+        path: &Path,
+        _from_lang: &str,
+        _to_lang: &str,
+    ) -> Result<PathBuf> {
+        // Keep the original path so dry runs show where translations would be written.
+        Ok(path.to_owned())
+    }
+
+    fn translate_list(
+        &self,
+        items: &[String],
+        _from_lang: &str,
+        _to_lang: &str,
+    ) -> Result<Vec<String>> {
+        Ok(items.to_vec())
     }
 
     fn translate_content(
         &self,
-        _text: &String,
-        _from_lang: &String,
-        _to_lang: &String,
+        _content: &Content,
+        _from_lang: &str,
+        _to_lang: &str,
         _source_hash: String,
-    ) -> Result<String, Box<dyn Error>> {
+        _correction: Option<&str>,
+    ) -> Result<String> {
         Ok("DRY_RUN".to_string())
     }
 }
 
-fn wait_for_user_input() {
+/// Only renders prompts, for translation in another tool (see `--prompt-only`).
+struct PromptOnlyTranslator {
+    generator: String,
+    prompts: Prompts,
+}
+
+impl PromptOnlyTranslator {
+    fn new(prompts: Prompts) -> Result<Self> {
+        dotenvy::dotenv().ok();
+        // NOTE: The generator is unknown, but prompts ask translators to mention it.
+        let generator = env::var("OPENAI_CHAT_MODEL").unwrap_or_else(|_| "UNKNOWN".to_string());
+
+        Ok(Self { generator, prompts })
+    }
+}
+
+impl Translator for PromptOnlyTranslator {
+    fn generator(&self) -> &str {
+        &self.generator
+    }
+
+    fn prompts(&self) -> &Prompts {
+        &self.prompts
+    }
+
+    fn max_concurrency(&self) -> usize {
+        usize::MAX
+    }
+
+    fn translate_path(
+        &self,
+        _path: &Path,
+        _from_lang: &str,
+        _to_lang: &str,
+    ) -> Result<PathBuf> {
+        Err(Error::PromptOnly)
+    }
+
+    fn translate_list(
+        &self,
+        _items: &[String],
+        _from_lang: &str,
+        _to_lang: &str,
+    ) -> Result<Vec<String>> {
+        Err(Error::PromptOnly)
+    }
+
+    fn translate_content(
+        &self,
+        _content: &Content,
+        _from_lang: &str,
+        _to_lang: &str,
+        _source_hash: String,
+        _correction: Option<&str>,
+    ) -> Result<String> {
+        Err(Error::PromptOnly)
+    }
+}
+
+/// Terminal to read from when stdin is not available for user input.
+#[cfg(windows)]
+const TERMINAL_PATH: &str = "CONIN$";
+#[cfg(not(windows))]
+const TERMINAL_PATH: &str = "/dev/tty";
+
+/// Waits for the user to hit [Enter], failing with [`Error::Interrupted`] if Ctrl-C was pressed meanwhile
+/// so the translation is recorded as failed instead of using whatever is in the clipboard.
+fn wait_for_user_input() -> Result<String> {
     let mut user_input = String::new();
-    match io::stdin().read_line(&mut user_input) {
-        Ok(_) => (),
-        Err(error) => {
-            eprintln!("Error reading input: {}", error);
-        },
+    // With `--stdin`, stdin is the document to translate (already read), so read from the terminal instead.
+    if io::stdin().read_line(&mut user_input)? == 0 {
+        let mut terminal = io::BufReader::new(fs::File::open(TERMINAL_PATH)?);
+        if terminal.read_line(&mut user_input)? == 0 {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into())
+        }
+    }
+    if crate::INTERRUPTED.load(Ordering::Relaxed) {
+        return Err(Error::Interrupted)
+    }
+    Ok(user_input)
+}
+
+/// Why a pasted translation probably isn't the answer to `prompt` (e.g. the wrong clipboard contents), if it seems so.
+///
+/// NOTE: This is only a heuristic (translations can't be compared to their source), so it only looks
+///   for the translation key, which translations must keep anyway.
+pub fn paste_mistake(pasted: &str, prompt: &str, content: &Content) -> Option<String> {
+    if pasted.trim() == prompt.trim() {
+        return Some("the clipboard still contains the prompt".to_string())
+    }
+    match content.translation_key {
+        Some(translation_key) if !pasted.contains(translation_key) => Some(format!("it doesn't contain the translation key '{}'", translation_key)),
+        _ => None,
+    }
+}
+
+/// Removes the code block models tend to wrap their answer in, as the prompt contains one.
+///
+/// NOTE: Content files start with their front matter, so the fence is only removed
+///   if it encloses the whole text and a front matter comes right after it.
+///   Code blocks which are part of the content are kept.
+fn strip_code_fence(text: String) -> String {
+    let trimmed = text.trim();
+    let Some((first_line, rest)) = trimmed.split_once('\n') else {
+        return text
+    };
+    let is_opening_fence = matches!(first_line.trim(), "```" | "```md" | "```markdown" | "```html");
+    let Some(inner) = rest.trim_end().strip_suffix("```") else {
+        return text
+    };
+    if !is_opening_fence || !inner.trim_start().starts_with(['-', '+', '{']) {
+        return text
+    }
+
+    inner.trim_end().to_string() + "\n"
+}
+
+/// Parses the JSON array answered to a [`Translator::list_translate_prompt`].
+fn parse_list(text: String) -> Result<Vec<String>> {
+    let trimmed = text.trim();
+    let json = trimmed.strip_prefix("```json").or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .unwrap_or(trimmed);
+    Ok(serde_json::from_str(json)?)
+}
+
+/// `clipboard` errors are not `Send`, keep their message only.
+fn clipboard_error(err: Box<dyn std::error::Error>) -> Error {
+    Error::Clipboard(err.to_string())
+}
+
+/// Checks the clipboard used for manual translation is available.
+pub fn probe_clipboard() -> Result<()> {
+    let _clipboard: ClipboardContext = ClipboardProvider::new().map_err(clipboard_error)?;
+    Ok(())
+}
+
+/// Loads `.env`, if there is one (variables can be set in the environment too).
+fn load_dotenv() -> Result<()> {
+    match dotenvy::dotenv() {
+        Err(err) if err.not_found() => Ok(()),
+        result => result.map(|_| ()).map_err(Error::from),
     }
 }
 
 struct GPTManualTranslator {
     model: String,
     clipboard: Mutex<ClipboardContext>,
+    prompts: Prompts,
 }
 
 impl GPTManualTranslator {
-    fn new() -> Result<Self, Box<dyn Error>> {
-        dotenvy::dotenv()?;
+    fn new(prompts: Prompts) -> Result<Self> {
+        load_dotenv()?;
+        // NOTE: The version of ChatGPT used for manual translation ("GPT-3.5", "GPT-4"…).
         let model = env::var("OPENAI_CHAT_MODEL")
-            .expect(r#"Environment variable `OPENAI_CHAT_MODEL` must be set to the version of ChatGPT used for manual translation ("GPT-3.5", "GPT-4"…)"#);
-        let clipboard: ClipboardContext = ClipboardProvider::new()?;
+            .map_err(|_| Error::MissingEnvVar("OPENAI_CHAT_MODEL", "translate manually"))?;
+        let clipboard: ClipboardContext = ClipboardProvider::new()
+            .map_err(clipboard_error)?;
 
-        Ok(Self { model, clipboard: Mutex::new(clipboard) })
+        Ok(Self { model, clipboard: Mutex::new(clipboard), prompts })
+    }
+
+    /// Locks the clipboard, even if a previous translation panicked while using it:
+    /// it holds no state of its own, so one failure shouldn't break the rest of the run.
+    fn lock_clipboard(&self) -> MutexGuard<'_, ClipboardContext> {
+        self.clipboard.lock().unwrap_or_else(PoisonError::into_inner)
     }
 }
 
@@ -131,83 +567,239 @@ impl Translator for GPTManualTranslator {
         &self.model
     }
 
+    fn prompts(&self) -> &Prompts {
+        &self.prompts
+    }
+
     fn translate_path(
         &self,
-        path: &PathBuf,
-        from_lang: &String,
-        to_lang: &String,
-    ) -> Result<PathBuf, Box<dyn Error>> {
-        let mut clipboard = self.clipboard.lock()
-            .map_err(|e| e.to_string())?;
+        path: &Path,
+        from_lang: &str,
+        to_lang: &str,
+    ) -> Result<PathBuf> {
+        let mut clipboard = self.lock_clipboard();
         let prompt = self.path_translate_prompt(path, from_lang, to_lang);
 
-        println!("Paste the following prompt into ChatGPT (it's already in your clipboard), copy the result, come back and hit [Enter]:\n> {}", prompt);
-        clipboard.set_contents(prompt)?;
-        wait_for_user_input();
-        clipboard.get_contents().map(PathBuf::from)
+        eprintln!("Paste the following prompt into ChatGPT (it's already in your clipboard), copy the result, come back and hit [Enter]:\n> {}", prompt);
+        clipboard.set_contents(prompt).map_err(clipboard_error)?;
+        wait_for_user_input()?;
+        clipboard.get_contents().map(PathBuf::from).map_err(clipboard_error)
+    }
+
+    fn translate_list(
+        &self,
+        items: &[String],
+        from_lang: &str,
+        to_lang: &str,
+    ) -> Result<Vec<String>> {
+        let mut clipboard = self.lock_clipboard();
+        let prompt = self.list_translate_prompt(items, from_lang, to_lang);
+
+        eprintln!("Paste the following prompt into ChatGPT (it's already in your clipboard), copy the result, come back and hit [Enter]:\n> {}", prompt);
+        clipboard.set_contents(prompt).map_err(clipboard_error)?;
+        wait_for_user_input()?;
+        parse_list(clipboard.get_contents().map_err(clipboard_error)?)
     }
 
     fn translate_content(
         &self,
-        text: &String,
-        from_lang: &String,
-        to_lang: &String,
+        content: &Content,
+        from_lang: &str,
+        to_lang: &str,
         source_hash: String,
-    ) -> Result<String, Box<dyn Error>> {
-        let mut clipboard = self.clipboard.lock()
-            .map_err(|e| e.to_string())?;
-        let prompt = self.content_translate_prompt(text, from_lang, to_lang, source_hash);
+        correction: Option<&str>,
+    ) -> Result<String> {
+        let mut clipboard = self.lock_clipboard();
+        let prompt = self.content_translate_prompt(content, from_lang, to_lang, source_hash, correction);
+
+        eprintln!("Paste the copied prompt into ChatGPT (it's already in your clipboard), copy the result, come back and hit [Enter]");
+        clipboard.set_contents(prompt.clone()).map_err(clipboard_error)?;
+        wait_for_user_input()?;
+        loop {
+            let pasted = clipboard.get_contents().map(strip_code_fence).map_err(clipboard_error)?;
+            let Some(mistake) = paste_mistake(&pasted, &prompt, content) else {
+                return Ok(pasted)
+            };
+            eprintln!("The copied result doesn't seem to be the '{}' translation you were asked for ({}). Copy the right result and hit [Enter], or type \"y\" and hit [Enter] to use it anyway.", to_lang, mistake);
+            if wait_for_user_input()?.trim().eq_ignore_ascii_case("y") {
+                return Ok(pasted)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelList {
+    data: Vec<Model>,
+}
 
-        println!("Paste the copied prompt into ChatGPT (it's already in your clipboard), copy the result, come back and hit [Enter]");
-        clipboard.set_contents(prompt)?;
-        wait_for_user_input();
-        clipboard.get_contents()
+#[derive(Debug, Deserialize)]
+struct Model {
+    id: String,
+}
+
+/// Lists models the account can use, so a typo in `OPENAI_API_MODEL` fails early with a clear message.
+///
+/// Returns the available ones among `models` (the preferred one, then fallbacks), failing if none is.
+fn check_models_are_available(api_key: &str, models: Vec<String>) -> Result<Vec<String>> {
+    let api_base = env::var("OPENAI_API_BASE").unwrap_or_else(|_| "https://api.openai.com/v1".to_string());
+    let response = minreq::get(format!("{}/models", api_base))
+        .with_header("Authorization", format!("Bearer {}", api_key))
+        .send()
+        .map_err(|err| Error::ModelListFailed(err.to_string()))?;
+    if response.status_code != 200 {
+        return Err(Error::ModelListFailed(format!("{} {}", response.status_code, response.reason_phrase)))
+    }
+    let model_list: ModelList = serde_json::from_slice(response.as_bytes())?;
+
+    let (available_models, unavailable_models): (Vec<String>, Vec<String>) = models.into_iter()
+        .partition(|model| model_list.data.iter().any(|m| &m.id == model));
+    if available_models.is_empty() {
+        let mut available = model_list.data.into_iter().map(|m| m.id).collect::<Vec<_>>();
+        available.sort();
+        return Err(Error::UnknownModel(unavailable_models.join("', '"), available.join(", ")))
     }
+    for model in unavailable_models {
+        eprintln!("Warning: Model '{}' is not available, it will not be used.", model);
+    }
+    Ok(available_models)
+}
+
+/// Whether an OpenAI error means another model could answer (e.g. unknown model, rate limit or overload).
+fn is_model_unavailable(err: &APIError) -> bool {
+    let message = err.message.to_lowercase();
+    ["model_not_found", "does not exist", "rate limit", "429", "overloaded", "capacity", "503"].iter()
+        .any(|pattern| message.contains(pattern))
 }
 
+/// Translates using OpenAI's chat completions API.
+///
+/// NOTE: Every request is a standalone chat completion (there is no shared assistant thread),
+///   so no context is carried from one file to the next and requests can run in parallel
+///   (see [`Translator::max_concurrency`]).
+///   With `--threads-per-key`, translations of the same page share a conversation instead
+///   (see [`Content::conversation`]).
 struct GPTAutoTranslator {
     client: Client,
     model: String,
+    /// Models used when the previous ones are unavailable (see `--model-fallback`).
+    fallback_models: Vec<String>,
+    prompts: Prompts,
+    stats: StatsRecorder,
+    /// Messages exchanged so far, by conversation, if conversations are enabled.
+    conversations: Option<Mutex<HashMap<String, Vec<ChatCompletionMessage>>>>,
+}
+
+fn user_message(content: String) -> ChatCompletionMessage {
+    ChatCompletionMessage {
+        role: MessageRole::user,
+        content,
+        name: None,
+        function_call: None,
+    }
+}
+
+fn system_message(content: String) -> ChatCompletionMessage {
+    ChatCompletionMessage {
+        role: MessageRole::system,
+        content,
+        name: None,
+        function_call: None,
+    }
+}
+
+fn assistant_message(content: String) -> ChatCompletionMessage {
+    ChatCompletionMessage {
+        role: MessageRole::assistant,
+        content,
+        name: None,
+        function_call: None,
+    }
 }
 
 impl GPTAutoTranslator {
-    fn new() -> Result<Self, Box<dyn Error>> {
-        dotenvy::dotenv()?;
-        let api_key = env::var("OPENAI_API_KEY").expect("The `OPENAI_API_KEY` environment variable must be defined.");
+    fn new(prompts: Prompts, args: &Args) -> Result<Self> {
+        load_dotenv()?;
+        let api_key = env::var("OPENAI_API_KEY").map_err(|_| Error::MissingEnvVar("OPENAI_API_KEY", "use `--auto`"))?;
         let model = match env::var("OPENAI_API_MODEL") {
             Ok(v) => v,
             Err(err) => {
                 let model = GPT3_5_TURBO_1106.to_string();
-                println!("`OPENAI_API_MODEL` environment variable not found ({}), using '{}'", err, model);
+                info!("`OPENAI_API_MODEL` environment variable not found ({}), using '{}'", err, model);
                 model
             },
         };
 
+        let mut models = vec![model];
+        models.extend(args.model_fallback.iter().cloned());
+        if !args.skip_model_check {
+            models = check_models_are_available(&api_key, models)?;
+        }
+        let model = models.remove(0);
+
         let client = Client::new(api_key);
 
         Ok(Self {
             client,
             model,
+            fallback_models: models,
+            prompts,
+            stats: StatsRecorder::default(),
+            conversations: args.threads_per_key.then(Mutex::default),
         })
     }
 
-    fn run(&self, prompt: String) -> Result<String, Box<dyn Error>> {
-        let client = &self.client;
+    fn run(&self, prompt: String) -> Result<String> {
+        self.run_with_fallback(None, |_| prompt.clone())
+    }
 
-        let req = ChatCompletionRequest::new(
-            self.model.clone(),
-            vec![ChatCompletionMessage {
-                role: MessageRole::user,
-                content: prompt,
-                name: None,
-                function_call: None,
-            }],
-        );
-
-        let result = client.chat_completion(req)?;
-        let response = result.choices[0].message.content.clone()
-            .ok_or(TranslationError::OpenAIError("Chat completion result contained no content".to_string()))?;
-        println!("Received {:?}", response);
+    /// Sends the prompt rendered by `prompt_for` a model, trying fallback models in order
+    /// while models are unavailable.
+    fn run_with_fallback(&self, conversation: Option<&str>, prompt_for: impl Fn(&str) -> String) -> Result<String> {
+        let mut models = std::iter::once(&self.model).chain(self.fallback_models.iter()).peekable();
+        loop {
+            let model = models.next().expect("There is at least one model");
+            match self.run_in(conversation, model, prompt_for(model)) {
+                Err(Error::OpenAI(err)) if is_model_unavailable(&err) && models.peek().is_some() => {
+                    eprintln!("Warning: Model '{}' is unavailable ({}), falling back to '{}'…", model, err.message, models.peek().unwrap());
+                },
+                result => return result,
+            }
+        }
+    }
+
+    /// Sends `prompt` after the previous messages of `conversation` (if any), and records the exchange.
+    ///
+    /// NOTE: Requests are sent without holding the lock, so concurrent requests of the same conversation
+    ///   don't see each other.
+    fn run_in(&self, conversation: Option<&str>, model: &str, prompt: String) -> Result<String> {
+        let client = &self.client;
+        let conversations = self.conversations.as_ref().zip(conversation);
+
+        let mut messages: Vec<ChatCompletionMessage> = self.prompts.context_instructions().map(system_message).into_iter().collect();
+        if let Some((conversations, conversation)) = conversations {
+            messages.extend(conversations.lock().unwrap().get(conversation).cloned().unwrap_or_default());
+        }
+        messages.push(user_message(prompt.clone()));
+        let req = ChatCompletionRequest::new(model.to_string(), messages);
+
+        let start = Instant::now();
+        let result = client.chat_completion(req);
+        self.stats.record_call(start);
+        let result = result?;
+        // Requests are answered synchronously (there is no run status to poll),
+        // but don't trust the API to always send a choice back.
+        let choice = result.choices.first()
+            .ok_or(Error::UnexpectedResponse("Chat completion result contained no choice".to_string()))?;
+        let response = choice.message.content.clone()
+            .ok_or(Error::UnexpectedResponse("Chat completion result contained no content".to_string()))?;
+
+        if let Some((conversations, conversation)) = conversations {
+            let mut conversations = conversations.lock().unwrap();
+            let messages = conversations.entry(conversation.to_string()).or_default();
+            messages.push(user_message(prompt));
+            messages.push(assistant_message(response.clone()));
+        }
 
         Ok(response)
     }
@@ -218,35 +810,55 @@ impl Translator for GPTAutoTranslator {
         &self.model
     }
 
+    fn prompts(&self) -> &Prompts {
+        &self.prompts
+    }
+
+    fn max_concurrency(&self) -> usize {
+        // Requests are independent chat completions, parallelism is only limited by OpenAI rate limits.
+        8
+    }
+
+    fn sends_context_separately(&self) -> bool {
+        true
+    }
+
+    fn stats(&self) -> Option<Stats> {
+        Some(self.stats.snapshot())
+    }
+
     fn translate_path(
         &self,
-        path: &PathBuf,
-        from_lang: &String,
-        to_lang: &String,
-    ) -> Result<PathBuf, Box<dyn Error>> {
+        path: &Path,
+        from_lang: &str,
+        to_lang: &str,
+    ) -> Result<PathBuf> {
         self.run(self.path_translate_prompt(path, from_lang, to_lang)).map(PathBuf::from)
     }
 
-    fn translate_content(
+    fn translate_list(
         &self,
-        text: &String,
-        from_lang: &String,
-        to_lang: &String,
-        source_hash: String,
-    ) -> Result<String, Box<dyn Error>> {
-        self.run(self.content_translate_prompt(text, from_lang, to_lang, source_hash))
+        items: &[String],
+        from_lang: &str,
+        to_lang: &str,
+    ) -> Result<Vec<String>> {
+        self.run(self.list_translate_prompt(items, from_lang, to_lang)).and_then(parse_list)
     }
-}
-
-#[derive(Debug, Clone)]
-enum TranslationError {
-    OpenAIError(String),
-}
 
-impl Display for TranslationError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Error: {:?}", self)
+    fn translate_content(
+        &self,
+        content: &Content,
+        from_lang: &str,
+        to_lang: &str,
+        source_hash: String,
+        correction: Option<&str>,
+    ) -> Result<String> {
+        if correction.is_some() {
+            self.stats.record_retry();
+        }
+        // Prompts mention the model, so translations tell which one actually translated them.
+        let prompt_for = |model: &str| self.content_translate_prompt_for(model, content, from_lang, to_lang, source_hash.clone(), correction);
+        self.run_with_fallback(content.conversation, prompt_for)
+            .map(strip_code_fence)
     }
 }
-
-impl Error for TranslationError {}